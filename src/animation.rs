@@ -0,0 +1,154 @@
+use crate::shape::Position;
+use nalgebra::base::Matrix4;
+
+/// A value that can be blended with other values of the same type.
+pub trait Interpolate: Sized {
+    /// the sum of each value multiplied by its weight. The weights add up
+    /// to 1, but may be negative.
+    fn blend(values: &[(f64, &Self)]) -> Self;
+}
+
+impl Interpolate for f64 {
+    fn blend(values: &[(f64, &f64)]) -> f64 {
+        values.iter().map(|(weight, value)| weight * *value).sum()
+    }
+}
+
+impl Interpolate for Position {
+    fn blend(values: &[(f64, &Position)]) -> Position {
+        values
+            .iter()
+            .fold(Position::ORIGIN, |total, (weight, value)| {
+                &total + &value.scale(*weight)
+            })
+    }
+}
+
+/// Matrices are interpolated element by element. This is exact for
+/// translation and scaling, but rotations will shrink part way between two
+/// keyframes that are far apart.
+impl Interpolate for Matrix4<f64> {
+    fn blend(values: &[(f64, &Matrix4<f64>)]) -> Matrix4<f64> {
+        values
+            .iter()
+            .fold(Matrix4::zeros(), |total, (weight, value)| {
+                total + *value * *weight
+            })
+    }
+}
+
+/// How a track moves from one keyframe to the next.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Interpolation {
+    /// move in a straight line at a constant speed.
+    Linear,
+    /// follow a smooth cubic bezier curve through every keyframe. The handles
+    /// of each keyframe point from the previous keyframe towards the next,
+    /// so the value changes without any sudden change in speed.
+    Bezier,
+}
+
+/// A value which changes over time, passing through each keyframe. Outside
+/// of the keyframes the value of the first or last keyframe is held.
+#[derive(Debug, Clone)]
+pub struct Track<T> {
+    interpolation: Interpolation,
+    keyframes: Vec<(f64, T)>,
+}
+
+impl<T: Interpolate + Clone> Track<T> {
+    /// keyframes are pairs of times and values. There must be at least one
+    /// keyframe.
+    pub fn new(interpolation: Interpolation, mut keyframes: Vec<(f64, T)>) -> Track<T> {
+        assert!(!keyframes.is_empty());
+        keyframes.sort_by(|(time1, _), (time2, _)| time1.partial_cmp(time2).unwrap());
+        Track {
+            interpolation,
+            keyframes,
+        }
+    }
+
+    /// a track which never changes.
+    pub fn constant(value: T) -> Track<T> {
+        Track::new(Interpolation::Linear, vec![(0.0, value)])
+    }
+
+    pub fn at(&self, time: f64) -> T {
+        let next = self
+            .keyframes
+            .iter()
+            .position(|(keyframe_time, _)| *keyframe_time > time);
+        let index = match next {
+            None => return self.keyframes[self.keyframes.len() - 1].1.clone(),
+            Some(0) => return self.keyframes[0].1.clone(),
+            Some(index) => index,
+        };
+
+        let (time1, _) = &self.keyframes[index - 1];
+        let (time2, _) = &self.keyframes[index];
+        let fraction = (time - time1) / (time2 - time1);
+        T::blend(&self.weights(index, fraction))
+    }
+
+    /// the values of the keyframes either side of the segment ending at the
+    /// keyframe at index, repeating the first and last keyframes at the ends.
+    fn neighbours(&self, index: usize) -> [&T; 4] {
+        let last = self.keyframes.len() - 1;
+        [
+            &self.keyframes[index.saturating_sub(2)].1,
+            &self.keyframes[index - 1].1,
+            &self.keyframes[index].1,
+            &self.keyframes[(index + 1).min(last)].1,
+        ]
+    }
+
+    /// the weight of each neighbouring keyframe part of the way through the
+    /// segment ending at the keyframe at index.
+    fn weights(&self, index: usize, fraction: f64) -> Vec<(f64, &T)> {
+        let [before, start, end, after] = self.neighbours(index);
+        match self.interpolation {
+            Interpolation::Linear => vec![(1.0 - fraction, start), (fraction, end)],
+            Interpolation::Bezier => {
+                // the handles are start + (end - before) / 6 and
+                // end - (after - start) / 6, expanded in terms of the
+                // neighbouring keyframes.
+                let inverse = 1.0 - fraction;
+                let b0 = inverse * inverse * inverse;
+                let b1 = 3.0 * fraction * inverse * inverse;
+                let b2 = 3.0 * fraction * fraction * inverse;
+                let b3 = fraction * fraction * fraction;
+                vec![
+                    (-b1 / 6.0, before),
+                    (b0 + b1 + b2 / 6.0, start),
+                    (b1 / 6.0 + b2 + b3, end),
+                    (-b2 / 6.0, after),
+                ]
+            }
+        }
+    }
+
+    /// values whose convex hull contains every value the track takes.
+    pub fn hull(&self) -> Vec<T> {
+        match self.interpolation {
+            Interpolation::Linear => self
+                .keyframes
+                .iter()
+                .map(|(_, value)| value.clone())
+                .collect(),
+            // a bezier curve lies within the hull of its keyframes and
+            // handles.
+            Interpolation::Bezier => (1..self.keyframes.len())
+                .flat_map(|index| {
+                    let [before, start, end, after] = self.neighbours(index);
+                    vec![
+                        start.clone(),
+                        T::blend(&[(1.0, start), (1.0 / 6.0, end), (-1.0 / 6.0, before)]),
+                        T::blend(&[(1.0, end), (-1.0 / 6.0, after), (1.0 / 6.0, start)]),
+                        end.clone(),
+                    ]
+                })
+                .chain(std::iter::once(self.keyframes[0].1.clone()))
+                .collect(),
+        }
+    }
+}
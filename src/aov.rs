@@ -0,0 +1,89 @@
+use crate::colour::Colour;
+use crate::image;
+use crate::ray::Ray;
+use crate::scene::Scene;
+
+use std::path::{Path, PathBuf};
+
+/// An arbitrary output variable. Each is a property of the first surface a
+/// camera ray hits, rendered as an extra image alongside the beauty pass for
+/// compositing and debugging. Pixels where nothing is hit are black.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Aov {
+    /// distance along the ray to the surface, in every channel.
+    Depth,
+    /// the x, y and z components of the surface normal.
+    Normal,
+    /// the x, y and z coordinates of the surface.
+    Position,
+    /// the base colour of the material.
+    Albedo,
+    /// the index of the body in the scene plus one, in every channel.
+    BodyIndex,
+    /// the light emitted by the surface.
+    Emission,
+}
+
+impl Aov {
+    pub const ALL: [Aov; 6] = [
+        Aov::Depth,
+        Aov::Normal,
+        Aov::Position,
+        Aov::Albedo,
+        Aov::BodyIndex,
+        Aov::Emission,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Aov::Depth => "depth",
+            Aov::Normal => "normal",
+            Aov::Position => "position",
+            Aov::Albedo => "albedo",
+            Aov::BodyIndex => "body_index",
+            Aov::Emission => "emission",
+        }
+    }
+
+    /// the path an output variable is written to, next to the beauty pass.
+    /// For example, image.ppm has a depth pass at image.depth.pfm.
+    pub fn path(&self, beauty: &Path) -> PathBuf {
+        image::sibling_path(beauty, self.name(), "pfm")
+    }
+
+    /// whether the pass holds a colour, which the reconstruction filter can
+    /// blend. The others hold data such as distances and ids, which are
+    /// averaged over the samples inside each pixel instead.
+    pub fn is_colour(&self) -> bool {
+        match self {
+            Aov::Albedo | Aov::Emission => true,
+            Aov::Depth | Aov::Normal | Aov::Position | Aov::BodyIndex => false,
+        }
+    }
+
+    pub fn evaluate(&self, scene: &Scene, ray: &Ray) -> Colour {
+        let (index, collision) = match scene.collision_index(ray) {
+            Some(hit) => hit,
+            None => return Colour::BLACK,
+        };
+        let body = &scene.bodies()[index];
+
+        match self {
+            Aov::Depth => Colour::new(collision.t(), collision.t(), collision.t()),
+            Aov::Normal => {
+                let normal = collision.normal();
+                Colour::new(normal.x(), normal.y(), normal.z())
+            }
+            Aov::Position => {
+                let position = collision.collision();
+                Colour::new(position.x(), position.y(), position.z())
+            }
+            Aov::Albedo => body.albedo(),
+            Aov::BodyIndex => {
+                let index = (index + 1) as f64;
+                Colour::new(index, index, index)
+            }
+            Aov::Emission => body.emission(),
+        }
+    }
+}
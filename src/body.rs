@@ -3,19 +3,28 @@ pub use basicbody::BasicBody;
 
 use crate::collision::Collision;
 use crate::colour::Colour;
-use crate::ray::Ray;
-use crate::shape::{Direction, Position};
+use crate::ray::{GeometricRay, Ray};
+use crate::shape::{Bounds, Direction, SurfaceSample};
 
 pub trait Body {
-    fn collision(&self, origin: &Position, direction: &Direction) -> Option<Collision>;
+    fn collision(&self, ray: &GeometricRay) -> Option<Collision>;
     fn rays(&self, ray_in: &Ray, collision: &Collision, efficacy: f64) -> Vec<Ray>;
     fn emission(&self) -> Colour;
     fn is_light(&self) -> bool;
+    fn albedo(&self) -> Colour;
+    fn sample_surface(&self) -> Option<SurfaceSample>;
+    fn bounds(&self) -> Option<Bounds>;
+    fn bsdf(
+        &self,
+        normal: &Direction,
+        incoming: &Direction,
+        outgoing: &Direction,
+    ) -> Option<Colour>;
 }
 
 impl<T: Body> Body for Box<T> {
-    fn collision(&self, origin: &Position, direction: &Direction) -> Option<Collision> {
-        (**self).collision(origin, direction)
+    fn collision(&self, ray: &GeometricRay) -> Option<Collision> {
+        (**self).collision(ray)
     }
     fn rays(&self, ray_in: &Ray, collision: &Collision, efficacy: f64) -> Vec<Ray> {
         (**self).rays(ray_in, collision, efficacy)
@@ -26,11 +35,28 @@ impl<T: Body> Body for Box<T> {
     fn is_light(&self) -> bool {
         (**self).is_light()
     }
+    fn albedo(&self) -> Colour {
+        (**self).albedo()
+    }
+    fn sample_surface(&self) -> Option<SurfaceSample> {
+        (**self).sample_surface()
+    }
+    fn bounds(&self) -> Option<Bounds> {
+        (**self).bounds()
+    }
+    fn bsdf(
+        &self,
+        normal: &Direction,
+        incoming: &Direction,
+        outgoing: &Direction,
+    ) -> Option<Colour> {
+        (**self).bsdf(normal, incoming, outgoing)
+    }
 }
 
 impl<T: Body> Body for &T {
-    fn collision(&self, origin: &Position, direction: &Direction) -> Option<Collision> {
-        (*self).collision(origin, direction)
+    fn collision(&self, ray: &GeometricRay) -> Option<Collision> {
+        (*self).collision(ray)
     }
     fn rays(&self, ray_in: &Ray, collision: &Collision, efficacy: f64) -> Vec<Ray> {
         (*self).rays(ray_in, collision, efficacy)
@@ -41,4 +67,21 @@ impl<T: Body> Body for &T {
     fn is_light(&self) -> bool {
         (*self).is_light()
     }
+    fn albedo(&self) -> Colour {
+        (*self).albedo()
+    }
+    fn sample_surface(&self) -> Option<SurfaceSample> {
+        (*self).sample_surface()
+    }
+    fn bounds(&self) -> Option<Bounds> {
+        (*self).bounds()
+    }
+    fn bsdf(
+        &self,
+        normal: &Direction,
+        incoming: &Direction,
+        outgoing: &Direction,
+    ) -> Option<Colour> {
+        (*self).bsdf(normal, incoming, outgoing)
+    }
 }
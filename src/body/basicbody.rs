@@ -2,8 +2,8 @@ use crate::body::Body;
 use crate::collision::Collision;
 use crate::colour::Colour;
 use crate::material::Material;
-use crate::ray::Ray;
-use crate::shape::{Direction, Position, Shape};
+use crate::ray::{GeometricRay, Ray};
+use crate::shape::{Bounds, Direction, Shape, SurfaceSample};
 
 const SLIGHTLY_OFF_SURFACE: f64 = 0.0001;
 const TOTAL_INTERNAL_REFLECTIONS: usize = 15;
@@ -27,9 +27,10 @@ impl<'a, S: Shape, M: Material> Iterator for InternalRay<'a, S, M> {
         self.ray.clone().and_then(|ray| {
             let direction = ray.direction();
             let origin = ray.origin().move_along(direction, SLIGHTLY_OFF_SURFACE);
+            let geometry = GeometricRay::new(origin.clone(), direction.clone(), ray.time());
             self.body
                 .shape
-                .collision_in(&origin, direction)
+                .collision_in(&geometry)
                 .map(|collision: Collision| {
                     let distance = origin.dist(collision.collision());
                     let attenuation = self.body.material.refract_attenuation(distance);
@@ -59,8 +60,8 @@ impl<S: Shape, M: Material> BasicBody<S, M> {
 }
 
 impl<S: Shape, M: Material> Body for BasicBody<S, M> {
-    fn collision(&self, origin: &Position, direction: &Direction) -> Option<Collision> {
-        self.shape.collision(origin, direction)
+    fn collision(&self, ray: &GeometricRay) -> Option<Collision> {
+        self.shape.collision(ray)
     }
 
     fn emission(&self) -> Colour {
@@ -91,4 +92,21 @@ impl<S: Shape, M: Material> Body for BasicBody<S, M> {
     fn is_light(&self) -> bool {
         self.material.is_light()
     }
+    fn albedo(&self) -> Colour {
+        self.material.albedo()
+    }
+    fn sample_surface(&self) -> Option<SurfaceSample> {
+        self.shape.sample_surface()
+    }
+    fn bounds(&self) -> Option<Bounds> {
+        self.shape.bounds()
+    }
+    fn bsdf(
+        &self,
+        normal: &Direction,
+        incoming: &Direction,
+        outgoing: &Direction,
+    ) -> Option<Colour> {
+        self.material.bsdf(normal, incoming, outgoing)
+    }
 }
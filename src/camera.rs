@@ -1,76 +1,95 @@
-use crate::shape::{Direction, Position};
-use rand::Rng;
-
-/// A basic camera with rectillinear projection and a square apeture.
-pub struct Camera {
-    position: Position,
-    down: Direction,
-    right: Direction,
-    top_left: Position,
-    aperture_size: f64,
-    pixel_width: f64,
-    pixel_height: f64,
-    x_resolution: u32,
-    y_resolution: u32,
-}
+pub mod animated;
+pub mod aperture;
+pub mod fisheye;
+pub mod moving;
+pub mod orthographic;
+pub mod panoramic;
+pub mod perspective;
+pub mod shutter;
+pub mod stereo;
 
-impl Camera {
-    pub fn new(
-        position: Position,
-        focal_point: Position,
-        width: f64,
-        height: f64,
-        aperture_size: f64,
-        up: Direction,
-        x_resolution: u32,
-        y_resolution: u32,
-    ) -> Camera {
-        let direction = Direction::from_two_points(&position, &focal_point);
-        let length = direction.len();
+pub use animated::Animated;
+pub use aperture::Aperture;
+pub use fisheye::Fisheye;
+pub use moving::Moving;
+pub use orthographic::Orthographic;
+pub use panoramic::{Cubemap, Equirectangular, OmniStereo};
+pub use perspective::{Lens, Perspective};
+pub use shutter::Shutter;
+pub use stereo::{Eye, SideBySide, Stereo};
 
-        let right = direction.cross(&up).normalise();
-        let down = direction.cross(&right).normalise();
-        let top_left = focal_point
-            .move_along(&down, -(length * height / 2.0))
-            .move_along(&right, -(length * width / 2.0));
-        let pixel_width = length * width / (x_resolution as f64);
-        let pixel_height = length * height / (y_resolution as f64);
+use crate::shape::{Direction, Position};
 
-        Camera {
-            position,
-            down,
-            right,
-            top_left,
-            aperture_size,
-            pixel_width,
-            pixel_height,
-            x_resolution,
-            y_resolution,
-        }
+/// A camera determines which ray passes through each pixel of an image.
+pub trait Camera {
+    /// the number of pixels across and down the image.
+    fn resolution(&self) -> (u32, u32);
+    /// return the origin and direction of a ray passing through the point x,
+    /// y on the image at the given time. The top left corner of the image is
+    /// 0, 0 and each pixel is 1 wide. The direction is normalised. The ray may
+    /// still be random, for example to give depth of field. None means the
+    /// camera sees nothing through the point, which is then left black.
+    fn generate_ray(&self, x: f64, y: f64, time: f64) -> Option<(Position, Direction)>;
+    /// the times at which the shutter opens and closes. Each ray is sent at a
+    /// random time in between.
+    fn shutter(&self) -> (f64, f64) {
+        (0.0, 0.0)
     }
+}
 
-    pub fn resolution(&self) -> (u32, u32) {
-        (self.x_resolution, self.y_resolution)
+impl<T: Camera> Camera for Box<T> {
+    fn resolution(&self) -> (u32, u32) {
+        (**self).resolution()
+    }
+    fn generate_ray(&self, x: f64, y: f64, time: f64) -> Option<(Position, Direction)> {
+        (**self).generate_ray(x, y, time)
+    }
+    fn shutter(&self) -> (f64, f64) {
+        (**self).shutter()
     }
+}
 
-    pub fn generate_ray(&self, x: f64, y: f64) -> (Position, Direction) {
-        let mut rng = rand::thread_rng();
-        let start_dy: f64 = rng.gen();
-        let start_dx: f64 = rng.gen();
-        let end_dx: f64 = rng.gen();
-        let end_dy: f64 = rng.gen();
+impl<T: Camera> Camera for &T {
+    fn resolution(&self) -> (u32, u32) {
+        (*self).resolution()
+    }
+    fn generate_ray(&self, x: f64, y: f64, time: f64) -> Option<(Position, Direction)> {
+        (*self).generate_ray(x, y, time)
+    }
+    fn shutter(&self) -> (f64, f64) {
+        (*self).shutter()
+    }
+}
 
-        let start = self
-            .position
-            .move_along(&self.down, self.aperture_size * start_dy)
-            .move_along(&self.right, self.aperture_size * start_dx);
-        let end = self
-            .top_left
-            .move_along(&self.down, (y + end_dy) * self.pixel_height)
-            .move_along(&self.right, (x + end_dx) * self.pixel_width);
+/// Unit vectors pointing forwards, right and down from the point of view of a
+/// camera.
+#[derive(Debug, Clone)]
+pub struct Basis {
+    pub forwards: Direction,
+    pub right: Direction,
+    pub down: Direction,
+}
 
-        let direction = Direction::from_two_points(&start, &end).normalise();
+impl Basis {
+    /// the basis of a camera at position looking towards focal_point.
+    pub fn new(position: &Position, focal_point: &Position, up: &Direction) -> Basis {
+        let forwards = Direction::from_two_points(position, focal_point).normalise();
+        let right = forwards.cross(up).normalise();
+        let down = forwards.cross(&right).normalise();
+        Basis {
+            forwards,
+            right,
+            down,
+        }
+    }
 
-        (start, direction)
+    /// the direction forwards * x + right * y + down * z. The result is not
+    /// normalised.
+    pub fn direction(&self, forwards: f64, right: f64, down: f64) -> Direction {
+        Position::ORIGIN
+            .move_along(&self.forwards, forwards)
+            .move_along(&self.right, right)
+            .move_along(&self.down, down)
+            .to_direction()
     }
 }
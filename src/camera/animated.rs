@@ -0,0 +1,39 @@
+use crate::animation::Track;
+use crate::camera::Camera;
+use crate::shape::{Direction, Position};
+
+/// A camera whose position and focal point follow tracks over time. The
+/// camera is built for each ray from the position and focal point at the
+/// time of the ray.
+pub struct Animated<F> {
+    position: Track<Position>,
+    focal_point: Track<Position>,
+    camera: F,
+}
+
+impl<C: Camera, F: Fn(Position, Position) -> C> Animated<F> {
+    /// camera creates a camera given its position and focal point.
+    pub fn new(position: Track<Position>, focal_point: Track<Position>, camera: F) -> Animated<F> {
+        Animated {
+            position,
+            focal_point,
+            camera,
+        }
+    }
+
+    pub fn at(&self, time: f64) -> C {
+        (self.camera)(self.position.at(time), self.focal_point.at(time))
+    }
+}
+
+impl<C: Camera, F: Fn(Position, Position) -> C> Camera for Animated<F> {
+    fn resolution(&self) -> (u32, u32) {
+        self.at(0.0).resolution()
+    }
+    fn generate_ray(&self, x: f64, y: f64, time: f64) -> Option<(Position, Direction)> {
+        self.at(time).generate_ray(x, y, time)
+    }
+    fn shutter(&self) -> (f64, f64) {
+        self.at(0.0).shutter()
+    }
+}
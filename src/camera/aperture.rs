@@ -0,0 +1,76 @@
+use crate::sampler;
+use std::f64::consts::PI;
+
+/// The shape of the opening in a lens that light passes through. Out of focus
+/// highlights take on the shape of the aperture.
+#[derive(Debug, Clone)]
+pub enum Aperture {
+    /// an infinitely small aperture. Everything is in focus.
+    Pinhole,
+    /// a circular aperture with the given radius.
+    Circle { radius: f64 },
+    /// a regular polygon with the given circumradius, formed by the blades of
+    /// an iris. rotation is in radians. There must be at least 3 blades.
+    Polygon {
+        radius: f64,
+        blades: u32,
+        rotation: f64,
+    },
+}
+
+impl Aperture {
+    /// a polygonal aperture, see Aperture::Polygon.
+    pub fn polygon(radius: f64, blades: u32, rotation: f64) -> Aperture {
+        assert!(blades >= 3, "a polygonal aperture needs at least 3 blades");
+        Aperture::Polygon {
+            radius,
+            blades,
+            rotation,
+        }
+    }
+
+    /// a circular aperture for a lens with the given focal length, in the
+    /// same units as the scene, and f-number.
+    pub fn from_f_stop(focal_length: f64, f_stop: f64) -> Aperture {
+        Aperture::Circle {
+            radius: focal_length / (2.0 * f_stop),
+        }
+    }
+
+    /// a uniformly distributed random point on the aperture, relative to its
+    /// centre, as a distance right and down.
+    pub fn sample(&self) -> (f64, f64) {
+        match *self {
+            Aperture::Pinhole => (0.0, 0.0),
+            Aperture::Circle { radius } => {
+                let (u, v) = sampler::next_2d();
+                let distance = radius * f64::sqrt(u);
+                let angle = 2.0 * PI * v;
+                (distance * angle.cos(), distance * angle.sin())
+            }
+            Aperture::Polygon {
+                radius,
+                blades,
+                rotation,
+            } => {
+                // pick a triangle between the centre and one edge, then a
+                // point within it.
+                let edge = (sampler::next() * blades as f64)
+                    .floor()
+                    .min(blades as f64 - 1.0);
+                let angle1 = rotation + 2.0 * PI * edge / blades as f64;
+                let angle2 = rotation + 2.0 * PI * (edge + 1.0) / blades as f64;
+
+                let (mut a, mut b) = sampler::next_2d();
+                if a + b > 1.0 {
+                    a = 1.0 - a;
+                    b = 1.0 - b;
+                }
+                (
+                    radius * (a * angle1.cos() + b * angle2.cos()),
+                    radius * (a * angle1.sin() + b * angle2.sin()),
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use crate::camera::{Basis, Camera};
+use crate::shape::{Direction, Position};
+
+/// A camera with an equidistant fisheye projection. The angle between a ray
+/// and the centre of the image is proportional to the distance of its pixel
+/// from the centre. The field of view spans the largest circle that fits in
+/// the image, and may be up to 360 degrees. Pixels outside the circle are
+/// black.
+pub struct Fisheye {
+    position: Position,
+    basis: Basis,
+    field_of_view: f64,
+    x_resolution: u32,
+    y_resolution: u32,
+}
+
+impl Fisheye {
+    /// field_of_view is in radians.
+    pub fn new(
+        position: Position,
+        focal_point: Position,
+        field_of_view: f64,
+        up: Direction,
+        x_resolution: u32,
+        y_resolution: u32,
+    ) -> Fisheye {
+        Fisheye {
+            basis: Basis::new(&position, &focal_point, &up),
+            position,
+            field_of_view,
+            x_resolution,
+            y_resolution,
+        }
+    }
+}
+
+impl Camera for Fisheye {
+    fn resolution(&self) -> (u32, u32) {
+        (self.x_resolution, self.y_resolution)
+    }
+
+    fn generate_ray(&self, x: f64, y: f64, _time: f64) -> Option<(Position, Direction)> {
+        // coordinates relative to the centre of the image, where the edge of
+        // the image circle has a radius of 1.
+        let radius = self.x_resolution.min(self.y_resolution) as f64 / 2.0;
+        let u = (x - self.x_resolution as f64 / 2.0) / radius;
+        let v = (y - self.y_resolution as f64 / 2.0) / radius;
+
+        let distance = f64::sqrt(u * u + v * v);
+        if distance > 1.0 {
+            // outside the image circle.
+            return None;
+        }
+
+        let angle = distance * self.field_of_view / 2.0;
+        let rotation = v.atan2(u);
+        let direction = self.basis.direction(
+            angle.cos(),
+            angle.sin() * rotation.cos(),
+            angle.sin() * rotation.sin(),
+        );
+
+        Some((self.position.clone(), direction.normalise()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corners_are_outside_image_circle() {
+        let camera = Fisheye::new(
+            Position::ORIGIN,
+            Position::new(0.0, 1.0, 0.0),
+            std::f64::consts::PI,
+            Direction::new(0.0, 0.0, 1.0),
+            100,
+            100,
+        );
+        assert!(camera.generate_ray(1.0, 1.0, 0.0).is_none());
+        let (_, direction) = camera.generate_ray(50.0, 50.0, 0.0).unwrap();
+        assert!((direction.y() - 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,32 @@
+use crate::animation::Track;
+use crate::camera::Camera;
+use crate::shape::{Direction, Position};
+
+/// A camera which moves during the exposure, following a track of
+/// translations.
+pub struct Moving<C> {
+    camera: C,
+    translation: Track<Position>,
+}
+
+impl<C: Camera> Moving<C> {
+    pub fn new(translation: Track<Position>, camera: C) -> Moving<C> {
+        Moving {
+            camera,
+            translation,
+        }
+    }
+}
+
+impl<C: Camera> Camera for Moving<C> {
+    fn resolution(&self) -> (u32, u32) {
+        self.camera.resolution()
+    }
+    fn generate_ray(&self, x: f64, y: f64, time: f64) -> Option<(Position, Direction)> {
+        let (start, direction) = self.camera.generate_ray(x, y, time)?;
+        Some((&start + &self.translation.at(time), direction))
+    }
+    fn shutter(&self) -> (f64, f64) {
+        self.camera.shutter()
+    }
+}
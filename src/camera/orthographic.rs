@@ -0,0 +1,57 @@
+use crate::camera::{Basis, Camera};
+use crate::shape::{Direction, Position};
+
+/// A camera with orthographic projection. Every ray travels in the same
+/// direction, so objects appear the same size regardless of their distance.
+/// Useful for architectural elevations and plans.
+pub struct Orthographic {
+    basis: Basis,
+    top_left: Position,
+    pixel_width: f64,
+    pixel_height: f64,
+    x_resolution: u32,
+    y_resolution: u32,
+}
+
+impl Orthographic {
+    /// width and height are the size of the area captured, in world units,
+    /// centred on position.
+    pub fn new(
+        position: Position,
+        focal_point: Position,
+        width: f64,
+        height: f64,
+        up: Direction,
+        x_resolution: u32,
+        y_resolution: u32,
+    ) -> Orthographic {
+        let basis = Basis::new(&position, &focal_point, &up);
+        let top_left = position
+            .move_along(&basis.down, -height / 2.0)
+            .move_along(&basis.right, -width / 2.0);
+
+        Orthographic {
+            basis,
+            top_left,
+            pixel_width: width / (x_resolution as f64),
+            pixel_height: height / (y_resolution as f64),
+            x_resolution,
+            y_resolution,
+        }
+    }
+}
+
+impl Camera for Orthographic {
+    fn resolution(&self) -> (u32, u32) {
+        (self.x_resolution, self.y_resolution)
+    }
+
+    fn generate_ray(&self, x: f64, y: f64, _time: f64) -> Option<(Position, Direction)> {
+        let start = self
+            .top_left
+            .move_along(&self.basis.down, y * self.pixel_height)
+            .move_along(&self.basis.right, x * self.pixel_width);
+
+        Some((start, self.basis.forwards.clone()))
+    }
+}
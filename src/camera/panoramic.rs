@@ -0,0 +1,161 @@
+use crate::camera::{Basis, Camera, Eye};
+use crate::shape::{Direction, Position};
+use std::f64::consts::PI;
+
+/// A full 360 degree panoramic camera using an equirectangular projection.
+/// Longitude increases linearly across the image and latitude down it, with
+/// the focal point at the centre. Images should be twice as wide as they are
+/// tall.
+pub struct Equirectangular {
+    position: Position,
+    basis: Basis,
+    x_resolution: u32,
+    y_resolution: u32,
+}
+
+impl Equirectangular {
+    pub fn new(
+        position: Position,
+        focal_point: Position,
+        up: Direction,
+        x_resolution: u32,
+        y_resolution: u32,
+    ) -> Equirectangular {
+        Equirectangular {
+            basis: Basis::new(&position, &focal_point, &up),
+            position,
+            x_resolution,
+            y_resolution,
+        }
+    }
+}
+
+impl Camera for Equirectangular {
+    fn resolution(&self) -> (u32, u32) {
+        (self.x_resolution, self.y_resolution)
+    }
+
+    fn generate_ray(&self, x: f64, y: f64, _time: f64) -> Option<(Position, Direction)> {
+        let longitude = (x / self.x_resolution as f64 - 0.5) * 2.0 * PI;
+        let latitude = (0.5 - y / self.y_resolution as f64) * PI;
+        let direction = self.basis.direction(
+            latitude.cos() * longitude.cos(),
+            latitude.cos() * longitude.sin(),
+            -latitude.sin(),
+        );
+
+        Some((self.position.clone(), direction.normalise()))
+    }
+}
+
+/// An omni-directional stereo version of the equirectangular camera, seeing
+/// the scene from one eye. Rather than every ray starting at the same point,
+/// each ray starts on a circle with a diameter of the interocular distance,
+/// offset to the side of its direction of view. Looking in any direction
+/// around the horizon then gives the correct stereo effect when combined with
+/// the other eye.
+pub struct OmniStereo {
+    position: Position,
+    basis: Basis,
+    interocular: f64,
+    eye: Eye,
+    x_resolution: u32,
+    y_resolution: u32,
+}
+
+impl OmniStereo {
+    pub fn new(
+        position: Position,
+        focal_point: Position,
+        up: Direction,
+        interocular: f64,
+        eye: Eye,
+        x_resolution: u32,
+        y_resolution: u32,
+    ) -> OmniStereo {
+        OmniStereo {
+            basis: Basis::new(&position, &focal_point, &up),
+            position,
+            interocular,
+            eye,
+            x_resolution,
+            y_resolution,
+        }
+    }
+}
+
+impl Camera for OmniStereo {
+    fn resolution(&self) -> (u32, u32) {
+        (self.x_resolution, self.y_resolution)
+    }
+
+    fn generate_ray(&self, x: f64, y: f64, _time: f64) -> Option<(Position, Direction)> {
+        let longitude = (x / self.x_resolution as f64 - 0.5) * 2.0 * PI;
+        let latitude = (0.5 - y / self.y_resolution as f64) * PI;
+        let direction = self.basis.direction(
+            latitude.cos() * longitude.cos(),
+            latitude.cos() * longitude.sin(),
+            -latitude.sin(),
+        );
+        // the horizontal direction to the right of the direction of view.
+        let side = self.basis.direction(-longitude.sin(), longitude.cos(), 0.0);
+        let start = self
+            .position
+            .move_along(&side, self.eye.sign() * self.interocular / 2.0);
+
+        Some((start, direction.normalise()))
+    }
+}
+
+/// A full 360 degree panoramic camera rendering the six faces of a cube
+/// around the camera side by side. From left to right the faces look
+/// forwards, right, backwards, left, up and down. Images should be six times
+/// as wide as they are tall.
+pub struct Cubemap {
+    position: Position,
+    basis: Basis,
+    face_resolution: u32,
+}
+
+impl Cubemap {
+    /// face_resolution is the width and height of each face in pixels.
+    pub fn new(
+        position: Position,
+        focal_point: Position,
+        up: Direction,
+        face_resolution: u32,
+    ) -> Cubemap {
+        Cubemap {
+            basis: Basis::new(&position, &focal_point, &up),
+            position,
+            face_resolution,
+        }
+    }
+}
+
+impl Camera for Cubemap {
+    fn resolution(&self) -> (u32, u32) {
+        (self.face_resolution * 6, self.face_resolution)
+    }
+
+    fn generate_ray(&self, x: f64, y: f64, _time: f64) -> Option<(Position, Direction)> {
+        let size = self.face_resolution as f64;
+        let face = (x / size).floor();
+        // coordinates on the face, between -1 and 1.
+        let u = ((x - face * size) / size) * 2.0 - 1.0;
+        let v = (y / size) * 2.0 - 1.0;
+
+        // the forwards, right and down components of the ray for each face.
+        let (forwards, right, down) = match face as u32 {
+            0 => (1.0, u, v),
+            1 => (-u, 1.0, v),
+            2 => (-1.0, -u, v),
+            3 => (u, -1.0, v),
+            4 => (v, u, -1.0),
+            _ => (-v, u, 1.0),
+        };
+        let direction = self.basis.direction(forwards, right, down);
+
+        Some((self.position.clone(), direction.normalise()))
+    }
+}
@@ -0,0 +1,208 @@
+use crate::camera::{Aperture, Basis, Camera};
+use crate::shape::{Bounds, Direction, Position};
+
+/// the length of the diagonal of a 35mm film frame in millimetres.
+const FULL_FRAME_DIAGONAL: f64 = 43.267;
+
+/// The lens of a perspective camera.
+#[derive(Debug, Clone)]
+pub struct Lens {
+    pub aperture: Aperture,
+    /// the distance in front of the camera which is in perfect focus.
+    pub focus_distance: f64,
+    /// the anamorphic squeeze factor. Out of focus highlights are stretched
+    /// vertically by this amount. Use 1.0 for a spherical lens.
+    pub squeeze: f64,
+}
+
+/// A camera with rectillinear projection and a thin lens, giving depth of
+/// field.
+pub struct Perspective {
+    position: Position,
+    basis: Basis,
+    lens: Lens,
+    width: f64,
+    height: f64,
+    /// how far the image is moved right at a distance of 1.
+    shift: f64,
+    x_resolution: u32,
+    y_resolution: u32,
+}
+
+impl Perspective {
+    /// width and height are the size of the image at a distance of 1 from
+    /// the camera.
+    pub fn new(
+        position: Position,
+        focal_point: Position,
+        width: f64,
+        height: f64,
+        lens: Lens,
+        up: Direction,
+        x_resolution: u32,
+        y_resolution: u32,
+    ) -> Perspective {
+        if let Aperture::Polygon { blades, .. } = lens.aperture {
+            assert!(blades >= 3, "a polygonal aperture needs at least 3 blades");
+        }
+        Perspective {
+            basis: Basis::new(&position, &focal_point, &up),
+            position,
+            lens,
+            width,
+            height,
+            shift: 0.0,
+            x_resolution,
+            y_resolution,
+        }
+    }
+
+    /// the same camera with the image moved right by shift at a distance of
+    /// 1, without turning the camera, like a shift lens.
+    pub fn with_shift(self, shift: f64) -> Perspective {
+        Perspective { shift, ..self }
+    }
+
+    /// a camera with the given vertical field of view in radians. The
+    /// horizontal field of view follows from the aspect ratio of the
+    /// resolution.
+    pub fn from_vertical_fov(
+        position: Position,
+        focal_point: Position,
+        field_of_view: f64,
+        lens: Lens,
+        up: Direction,
+        x_resolution: u32,
+        y_resolution: u32,
+    ) -> Perspective {
+        let height = 2.0 * f64::tan(field_of_view / 2.0);
+        let width = height * x_resolution as f64 / y_resolution as f64;
+        Perspective::new(
+            position,
+            focal_point,
+            width,
+            height,
+            lens,
+            up,
+            x_resolution,
+            y_resolution,
+        )
+    }
+
+    /// a camera with the given horizontal field of view in radians. The
+    /// vertical field of view follows from the aspect ratio of the
+    /// resolution.
+    pub fn from_horizontal_fov(
+        position: Position,
+        focal_point: Position,
+        field_of_view: f64,
+        lens: Lens,
+        up: Direction,
+        x_resolution: u32,
+        y_resolution: u32,
+    ) -> Perspective {
+        let width = 2.0 * f64::tan(field_of_view / 2.0);
+        let height = width * y_resolution as f64 / x_resolution as f64;
+        Perspective::new(
+            position,
+            focal_point,
+            width,
+            height,
+            lens,
+            up,
+            x_resolution,
+            y_resolution,
+        )
+    }
+
+    /// a camera with the same diagonal field of view as a lens with the given
+    /// focal length in millimetres on a 35mm full frame camera.
+    pub fn from_focal_length(
+        position: Position,
+        focal_point: Position,
+        focal_length: f64,
+        lens: Lens,
+        up: Direction,
+        x_resolution: u32,
+        y_resolution: u32,
+    ) -> Perspective {
+        let diagonal = FULL_FRAME_DIAGONAL / focal_length;
+        let aspect = x_resolution as f64 / y_resolution as f64;
+        let height = diagonal / f64::sqrt(aspect * aspect + 1.0);
+        Perspective::new(
+            position,
+            focal_point,
+            height * aspect,
+            height,
+            lens,
+            up,
+            x_resolution,
+            y_resolution,
+        )
+    }
+
+    /// a camera looking along direction with the given vertical field of
+    /// view, placed as close as possible while keeping all of bounds in view.
+    /// The lens is focused on the centre of bounds.
+    pub fn frame(
+        bounds: &Bounds,
+        direction: &Direction,
+        field_of_view: f64,
+        lens: Lens,
+        up: Direction,
+        x_resolution: u32,
+        y_resolution: u32,
+    ) -> Perspective {
+        let half_height = f64::tan(field_of_view / 2.0);
+        let half_width = half_height * x_resolution as f64 / y_resolution as f64;
+        // the narrowest half angle of the field of view must contain the
+        // bounding sphere.
+        let half_angle = half_height.min(half_width).atan();
+        let distance = bounds.radius() / half_angle.sin();
+
+        let focal_point = bounds.centre();
+        let position = focal_point.move_along(&direction.normalise(), -distance);
+        Perspective::from_vertical_fov(
+            position,
+            focal_point,
+            field_of_view,
+            Lens {
+                focus_distance: distance,
+                ..lens
+            },
+            up,
+            x_resolution,
+            y_resolution,
+        )
+    }
+}
+
+impl Camera for Perspective {
+    fn resolution(&self) -> (u32, u32) {
+        (self.x_resolution, self.y_resolution)
+    }
+
+    fn generate_ray(&self, x: f64, y: f64, _time: f64) -> Option<(Position, Direction)> {
+        // the direction of a ray through the centre of the lens. It has a
+        // forwards component of 1, so it reaches the plane of focus after
+        // focus_distance.
+        let centre_direction = self.basis.direction(
+            1.0,
+            (x / self.x_resolution as f64 - 0.5) * self.width + self.shift,
+            (y / self.y_resolution as f64 - 0.5) * self.height,
+        );
+        let end = self
+            .position
+            .move_along(&centre_direction, self.lens.focus_distance);
+
+        let (right, down) = self.lens.aperture.sample();
+        let start = self
+            .position
+            .move_along(&self.basis.right, right)
+            .move_along(&self.basis.down, down * self.lens.squeeze);
+
+        let direction = Direction::from_two_points(&start, &end).normalise();
+
+        Some((start, direction))
+    }
+}
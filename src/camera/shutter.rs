@@ -0,0 +1,33 @@
+use crate::camera::Camera;
+use crate::shape::{Direction, Position};
+
+/// Keeps the shutter of a camera open between two times, so that anything
+/// moving in the scene is blurred.
+pub struct Shutter<C> {
+    camera: C,
+    open: f64,
+    close: f64,
+}
+
+impl<C: Camera> Shutter<C> {
+    pub fn new(open: f64, close: f64, camera: C) -> Shutter<C> {
+        assert!(open <= close);
+        Shutter {
+            camera,
+            open,
+            close,
+        }
+    }
+}
+
+impl<C: Camera> Camera for Shutter<C> {
+    fn resolution(&self) -> (u32, u32) {
+        self.camera.resolution()
+    }
+    fn generate_ray(&self, x: f64, y: f64, time: f64) -> Option<(Position, Direction)> {
+        self.camera.generate_ray(x, y, time)
+    }
+    fn shutter(&self) -> (f64, f64) {
+        (self.open, self.close)
+    }
+}
@@ -0,0 +1,135 @@
+use crate::camera::{Basis, Camera};
+use crate::shape::{Direction, Position};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Eye {
+    Left,
+    Right,
+}
+
+impl Eye {
+    /// -1 for the left eye and 1 for the right eye.
+    pub fn sign(self) -> f64 {
+        match self {
+            Eye::Left => -1.0,
+            Eye::Right => 1.0,
+        }
+    }
+}
+
+/// A pair of cameras for rendering stereo images. The eyes are placed either
+/// side of position and look straight ahead in parallel, with their images
+/// shifted towards each other so they line up at the convergence distance.
+/// Objects at that distance appear at the depth of the screen.
+pub struct Stereo<F> {
+    position: Position,
+    basis: Basis,
+    interocular: f64,
+    convergence: f64,
+    camera: F,
+}
+
+impl<C: Camera, F: Fn(Position, Position, f64) -> C> Stereo<F> {
+    /// camera creates the camera for an eye given its position, focal point
+    /// and how far its image should be moved right at a distance of 1, as
+    /// with Perspective::with_shift.
+    pub fn new(
+        position: Position,
+        focal_point: Position,
+        up: Direction,
+        interocular: f64,
+        convergence: f64,
+        camera: F,
+    ) -> Stereo<F> {
+        Stereo {
+            basis: Basis::new(&position, &focal_point, &up),
+            position,
+            interocular,
+            convergence,
+            camera,
+        }
+    }
+
+    pub fn eye(&self, eye: Eye) -> C {
+        let position = self
+            .position
+            .move_along(&self.basis.right, eye.sign() * self.interocular / 2.0);
+        let focal_point = position.move_along(&self.basis.forwards, self.convergence);
+        // the centre of the rig at the convergence distance is this far
+        // across from the eye.
+        let shift = -eye.sign() * self.interocular / 2.0 / self.convergence;
+        (self.camera)(position, focal_point, shift)
+    }
+}
+
+/// Two cameras rendered next to each other in the same image, such as the
+/// left and right eyes of a stereo pair. Both cameras should have the same
+/// vertical resolution.
+pub struct SideBySide<L, R> {
+    left: L,
+    right: R,
+}
+
+impl<L: Camera, R: Camera> SideBySide<L, R> {
+    pub fn new(left: L, right: R) -> SideBySide<L, R> {
+        assert_eq!(left.resolution().1, right.resolution().1);
+        SideBySide { left, right }
+    }
+}
+
+impl<L: Camera, R: Camera> Camera for SideBySide<L, R> {
+    fn resolution(&self) -> (u32, u32) {
+        let (left_width, height) = self.left.resolution();
+        (left_width + self.right.resolution().0, height)
+    }
+    fn generate_ray(&self, x: f64, y: f64, time: f64) -> Option<(Position, Direction)> {
+        let left_width = self.left.resolution().0 as f64;
+        if x < left_width {
+            self.left.generate_ray(x, y, time)
+        } else {
+            self.right.generate_ray(x - left_width, y, time)
+        }
+    }
+    fn shutter(&self) -> (f64, f64) {
+        self.left.shutter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera::{Aperture, Lens, Perspective};
+
+    #[test]
+    fn eyes_meet_at_convergence() {
+        let stereo = Stereo::new(
+            Position::ORIGIN,
+            Position::new(0.0, 1.0, 0.0),
+            Direction::UP,
+            6.0,
+            30.0,
+            |position, focal_point, shift| {
+                let lens = Lens {
+                    aperture: Aperture::Pinhole,
+                    focus_distance: 1.0,
+                    squeeze: 1.0,
+                };
+                Perspective::new(position, focal_point, 1.0, 1.0, lens, Direction::UP, 10, 10)
+                    .with_shift(shift)
+            },
+        );
+        // with parallel eyes, each pixel of both eyes sees the same point
+        // on the plane at the convergence distance, not just the centre.
+        for (x, y) in [(5.0, 5.0), (1.0, 8.0), (9.5, 0.5)].iter() {
+            let hits: Vec<Position> = [Eye::Left, Eye::Right]
+                .iter()
+                .map(|eye| {
+                    let (start, direction) = stereo.eye(*eye).generate_ray(*x, *y, 0.0).unwrap();
+                    start.move_along(&direction, (30.0 - start.y()) / direction.y())
+                })
+                .collect();
+            let gap = Direction::from_two_points(&hits[0], &hits[1]);
+            assert!(gap.len() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use crate::aov::Aov;
+use crate::colour::Colour;
+use crate::image::Image;
+use crate::scene::Render;
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+// prevents division by zero for pixels with no noise.
+const MIN_VARIANCE: f64 = 1e-10;
+
+/// A joint bilateral filter for removing noise from renders with few samples.
+/// Each pixel is replaced by a weighted average of its neighbours, where
+/// neighbours are only given weight if they appear to lie on the same surface,
+/// judging by the normal, depth and albedo passes of the render. Passes which
+/// were not rendered are not used.
+///
+/// Neighbours with very different colours are also ignored, with the
+/// difference measured relative to the estimated noise in each pixel. Noisy
+/// pixels are smoothed heavily, while clean edges and texture are kept.
+pub struct Denoiser {
+    /// the number of pixels either side of a pixel that are averaged.
+    pub radius: u32,
+    /// the distance in pixels at which neighbours lose most of their weight.
+    pub spatial_sigma: f64,
+    /// the distance between two unit normals at which neighbours lose most of
+    /// their weight.
+    pub normal_sigma: f64,
+    /// the difference in depth, as a fraction of the depth of the pixel, at
+    /// which neighbours lose most of their weight.
+    pub depth_sigma: f64,
+    /// the difference in albedo at which neighbours lose most of their weight.
+    pub albedo_sigma: f64,
+    /// the difference in colour, in standard deviations of the noise, at
+    /// which neighbours lose most of their weight.
+    pub colour_sigma: f64,
+}
+
+fn dist_sq(colour1: &Colour, colour2: &Colour) -> f64 {
+    let difference = colour1 - colour2;
+    difference.red() * difference.red()
+        + difference.green() * difference.green()
+        + difference.blue() * difference.blue()
+}
+
+fn gaussian(dist_sq: f64, sigma: f64) -> f64 {
+    f64::exp(-dist_sq / (2.0 * sigma * sigma))
+}
+
+impl Denoiser {
+    pub fn denoise(&self, render: &Render) -> Image {
+        let beauty = &render.beauty;
+        let (width, height) = (beauty.width(), beauty.height());
+
+        let rows: Vec<Vec<Colour>> = (0..height)
+            .into_par_iter()
+            .map(|y| (0..width).map(|x| self.filter(render, x, y)).collect())
+            .collect();
+
+        let mut image = Image::new(width, height);
+        for (y, row) in rows.into_iter().enumerate() {
+            for (x, colour) in row.into_iter().enumerate() {
+                image.set(x as u32, y as u32, colour);
+            }
+        }
+        image
+    }
+
+    fn filter(&self, render: &Render, x: u32, y: u32) -> Colour {
+        let beauty = &render.beauty;
+        let x_min = x.saturating_sub(self.radius);
+        let y_min = y.saturating_sub(self.radius);
+        let x_max = (x + self.radius).min(beauty.width() - 1);
+        let y_max = (y + self.radius).min(beauty.height() - 1);
+
+        let mut total = Colour::BLACK;
+        let mut total_weight = 0.0;
+        for y2 in y_min..=y_max {
+            for x2 in x_min..=x_max {
+                let weight = self.weight(render, (x, y), (x2, y2));
+                total = &total + &beauty.get(x2, y2).brighten(weight);
+                total_weight += weight;
+            }
+        }
+        total.brighten(1.0 / total_weight)
+    }
+
+    fn weight(&self, render: &Render, (x1, y1): (u32, u32), (x2, y2): (u32, u32)) -> f64 {
+        let dx = x1 as f64 - x2 as f64;
+        let dy = y1 as f64 - y2 as f64;
+        let mut weight = gaussian(dx * dx + dy * dy, self.spatial_sigma);
+
+        if let Some(normal) = render.aov(Aov::Normal) {
+            let difference = dist_sq(normal.get(x1, y1), normal.get(x2, y2));
+            weight *= gaussian(difference, self.normal_sigma);
+        }
+        if let Some(albedo) = render.aov(Aov::Albedo) {
+            let difference = dist_sq(albedo.get(x1, y1), albedo.get(x2, y2));
+            weight *= gaussian(difference, self.albedo_sigma);
+        }
+        if let Some(depth) = render.aov(Aov::Depth) {
+            let depth1 = depth.get(x1, y1).red();
+            let depth2 = depth.get(x2, y2).red();
+            let difference = (depth1 - depth2) / depth1.max(MIN_VARIANCE);
+            weight *= gaussian(difference * difference, self.depth_sigma);
+        }
+
+        let beauty = &render.beauty;
+        let variance = &render.variance;
+        let noise = variance.get(x1, y1) + variance.get(x2, y2);
+        let difference = beauty.get(x1, y1) - beauty.get(x2, y2);
+        let colour_distance = [
+            (difference.red(), noise.red()),
+            (difference.green(), noise.green()),
+            (difference.blue(), noise.blue()),
+        ]
+        .iter()
+        .map(|(difference, noise)| difference * difference / (noise + MIN_VARIANCE))
+        .sum::<f64>()
+            / 3.0;
+        weight * gaussian(colour_distance, self.colour_sigma)
+    }
+}
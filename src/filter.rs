@@ -0,0 +1,145 @@
+use std::f64::consts::PI;
+
+/// A pixel reconstruction filter. Each sample is spread over the pixels
+/// around it, weighted by the filter according to its distance from the
+/// centre of each pixel. Wider filters give smoother edges at the cost of
+/// some sharpness. The filter is applied separately in x and y, and is
+/// scaled so it integrates to 1.
+#[derive(Debug, Clone)]
+pub enum Filter {
+    /// every sample within radius has the same weight. A radius of 0.5
+    /// averages the samples within each pixel.
+    Box { radius: f64 },
+    /// the weight falls linearly to 0 at radius.
+    Tent { radius: f64 },
+    /// a gaussian with weight e^(-alpha x^2), shifted down to reach 0 at
+    /// radius.
+    Gaussian { radius: f64, alpha: f64 },
+    /// the Mitchell-Netravali cubic, stretched to reach 0 at radius. b and c
+    /// trade blurring against ringing; 1/3 and 1/3 are recommended.
+    MitchellNetravali { radius: f64, b: f64, c: f64 },
+    /// a sinc function windowed by a wider sinc, reaching 0 at radius. Very
+    /// sharp, but rings around bright edges. It is left unscaled, as it
+    /// integrates to within 1% of 1 for a radius of 2 or more.
+    Lanczos { radius: f64 },
+}
+
+/// the error function, to within 1.5e-7 (Abramowitz and Stegun 7.1.26).
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.327_591_1 * x.abs());
+    let polynomial = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    (1.0 - polynomial * f64::exp(-x * x)).copysign(x)
+}
+
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-5 {
+        1.0
+    } else {
+        f64::sin(PI * x) / (PI * x)
+    }
+}
+
+impl Filter {
+    /// the distance from the centre of a pixel beyond which samples are
+    /// ignored.
+    pub fn radius(&self) -> f64 {
+        match *self {
+            Filter::Box { radius } => radius,
+            Filter::Tent { radius } => radius,
+            Filter::Gaussian { radius, .. } => radius,
+            Filter::MitchellNetravali { radius, .. } => radius,
+            Filter::Lanczos { radius } => radius,
+        }
+    }
+
+    /// the weight of a sample offset from the centre of a pixel by x, y. May
+    /// be negative.
+    pub fn weight(&self, x: f64, y: f64) -> f64 {
+        self.weight_1d(x) * self.weight_1d(y)
+    }
+
+    fn weight_1d(&self, x: f64) -> f64 {
+        let x = x.abs();
+        if x > self.radius() {
+            return 0.0;
+        }
+        match *self {
+            Filter::Box { radius } => 1.0 / (2.0 * radius),
+            Filter::Tent { radius } => (radius - x) / (radius * radius),
+            Filter::Gaussian { radius, alpha } => {
+                let edge = f64::exp(-alpha * radius * radius);
+                let integral =
+                    f64::sqrt(PI / alpha) * erf(radius * alpha.sqrt()) - 2.0 * radius * edge;
+                (f64::exp(-alpha * x * x) - edge) / integral
+            }
+            Filter::MitchellNetravali { radius, b, c } => {
+                // the cubic is defined between -2 and 2, where it integrates
+                // to 1, so stretching it scales its integral by radius / 2.
+                let x = 2.0 * x / radius;
+                let cubic = if x < 1.0 {
+                    ((12.0 - 9.0 * b - 6.0 * c) * x * x * x
+                        + (-18.0 + 12.0 * b + 6.0 * c) * x * x
+                        + (6.0 - 2.0 * b))
+                        / 6.0
+                } else {
+                    ((-b - 6.0 * c) * x * x * x
+                        + (6.0 * b + 30.0 * c) * x * x
+                        + (-12.0 * b - 48.0 * c) * x
+                        + (8.0 * b + 24.0 * c))
+                        / 6.0
+                };
+                cubic * 2.0 / radius
+            }
+            Filter::Lanczos { radius } => sinc(x) * sinc(x / radius),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_integrate_to_one() {
+        let filters = [
+            Filter::Box { radius: 0.5 },
+            Filter::Box { radius: 1.5 },
+            Filter::Tent { radius: 2.0 },
+            Filter::Gaussian {
+                radius: 2.0,
+                alpha: 2.0,
+            },
+            Filter::MitchellNetravali {
+                radius: 2.0,
+                b: 1.0 / 3.0,
+                c: 1.0 / 3.0,
+            },
+            Filter::MitchellNetravali {
+                radius: 3.0,
+                b: 0.0,
+                c: 0.5,
+            },
+            Filter::Lanczos { radius: 3.0 },
+        ];
+        for filter in filters.iter() {
+            let steps = 400;
+            let step = 2.0 * filter.radius() / steps as f64;
+            let offset = |i: usize| -filter.radius() + (i as f64 + 0.5) * step;
+            let mut integral = 0.0;
+            for i in 0..steps {
+                for j in 0..steps {
+                    integral += filter.weight(offset(i), offset(j)) * step * step;
+                }
+            }
+            assert!(
+                (integral - 1.0).abs() < 0.02,
+                "{:?} gave {}",
+                filter,
+                integral
+            );
+        }
+    }
+}
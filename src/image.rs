@@ -0,0 +1,182 @@
+use crate::colour::Colour;
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A floating point framebuffer. Pixels are stored row by row, starting at the
+/// top left.
+#[derive(Debug, Clone)]
+pub struct Image {
+    width: u32,
+    height: u32,
+    pixels: Vec<Colour>,
+}
+
+impl Image {
+    /// create a black image.
+    pub fn new(width: u32, height: u32) -> Image {
+        Image {
+            width,
+            height,
+            pixels: vec![Colour::BLACK; (width * height) as usize],
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> &Colour {
+        &self.pixels[(y * self.width + x) as usize]
+    }
+    pub fn set(&mut self, x: u32, y: u32, colour: Colour) {
+        self.pixels[(y * self.width + x) as usize] = colour;
+    }
+
+    /// an 8 bit binary PPM image. Colours are tone mapped to fit in a byte.
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut bytes = format!("P6 {} {} 255 ", self.width, self.height)
+            .as_bytes()
+            .to_vec();
+        for pixel in &self.pixels {
+            bytes.append(&mut pixel.to_bytes());
+        }
+        bytes
+    }
+
+    /// a PFM image. Colours are written unchanged as 32 bit floats, which is
+    /// useful for compositing.
+    pub fn to_pfm(&self) -> Vec<u8> {
+        let mut bytes = format!("PF\n{} {}\n-1.0\n", self.width, self.height)
+            .as_bytes()
+            .to_vec();
+        // PFM images are stored from the bottom row to the top.
+        for row in self.pixels.chunks(self.width as usize).rev() {
+            for pixel in row {
+                for channel in &[pixel.red(), pixel.green(), pixel.blue()] {
+                    bytes.extend_from_slice(&(*channel as f32).to_le_bytes());
+                }
+            }
+        }
+        bytes
+    }
+
+    /// read an 8 bit binary PPM or PGM image, such as a heightmap. Unlike
+    /// the tone mapped output of to_ppm, each channel is scaled to lie
+    /// between 0 and 1.
+    pub fn read_ppm(path: &Path) -> io::Result<Image> {
+        let bytes = fs::read(path)?;
+        let invalid = |message| io::Error::new(io::ErrorKind::InvalidData, message);
+
+        // the header is the format, width, height and maximum value,
+        // separated by whitespace, with comments starting with #.
+        let mut header = Vec::new();
+        let mut position = 0;
+        while header.len() < 4 {
+            let start = position;
+            while position < bytes.len() && !bytes[position].is_ascii_whitespace() {
+                position += 1;
+            }
+            if position == bytes.len() {
+                return Err(invalid("truncated header"));
+            }
+            let token = String::from_utf8_lossy(&bytes[start..position]).into_owned();
+            if token.starts_with('#') {
+                while position < bytes.len() && bytes[position] != b'\n' {
+                    position += 1;
+                }
+            } else if !token.is_empty() {
+                header.push(token);
+            }
+            position += 1;
+        }
+
+        let channels = match header[0].as_str() {
+            "P5" => 1,
+            "P6" => 3,
+            _ => return Err(invalid("only binary PPM and PGM images are supported")),
+        };
+        let parse = |token: &str| token.parse::<u32>().map_err(|_| invalid("invalid header"));
+        let (width, height, max) = (parse(&header[1])?, parse(&header[2])?, parse(&header[3])?);
+        if max == 0 || max > 255 {
+            return Err(invalid("only 8 bit images are supported"));
+        }
+
+        // a corrupt header can claim far more pixels than could ever fit in
+        // memory.
+        let pixel_count = (width as usize)
+            .checked_mul(height as usize)
+            .ok_or_else(|| invalid("image too large"))?;
+        let data = &bytes[position..];
+        match pixel_count.checked_mul(channels) {
+            None => return Err(invalid("image too large")),
+            Some(length) if data.len() < length => return Err(invalid("truncated image")),
+            Some(_) => (),
+        }
+        let value = |index: usize| data[index] as f64 / max as f64;
+        let pixels = (0..pixel_count)
+            .map(|pixel| {
+                let index = pixel * channels;
+                if channels == 1 {
+                    Colour::new(value(index), value(index), value(index))
+                } else {
+                    Colour::new(value(index), value(index + 1), value(index + 2))
+                }
+            })
+            .collect();
+        Ok(Image {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    pub fn write_ppm(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_ppm())
+    }
+    pub fn write_pfm(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_pfm())
+    }
+}
+
+/// the path of an image related to another, in the same directory. For
+/// example, the depth pass of image.ppm is written to image.depth.pfm.
+pub fn sibling_path(path: &Path, name: &str, extension: &str) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map_or("image".into(), |stem| stem.to_string_lossy());
+    path.with_file_name(format!("{}.{}.{}", stem, name, extension))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn read_bytes(name: &str, bytes: &[u8]) -> io::Result<Image> {
+        let path = env::temp_dir().join(name);
+        fs::write(&path, bytes)?;
+        let image = Image::read_ppm(&path);
+        fs::remove_file(&path)?;
+        image
+    }
+
+    #[test]
+    fn reads_pgm() {
+        let image = read_bytes("raytrace_small.pgm", b"P5\n# comment\n2 1\n255\n\x00\xff").unwrap();
+        assert_eq!((image.width(), image.height()), (2, 1));
+        assert_eq!(image.get(1, 0).red(), 1.0);
+    }
+
+    #[test]
+    fn rejects_huge_and_truncated_images() {
+        let huge = read_bytes("raytrace_huge.ppm", b"P6 4294967295 4294967295 255\n\x00");
+        assert_eq!(huge.unwrap_err().kind(), io::ErrorKind::InvalidData);
+        let truncated = read_bytes("raytrace_truncated.ppm", b"P6 2 2 255\n\x00\x00\x00");
+        assert_eq!(truncated.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
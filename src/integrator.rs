@@ -0,0 +1,39 @@
+pub mod ambient_occlusion;
+pub mod bidirectional;
+pub mod debug;
+pub mod direct;
+mod light;
+pub mod path;
+pub mod photon;
+pub mod splitting;
+
+pub use ambient_occlusion::AmbientOcclusion;
+pub use bidirectional::Bidirectional;
+pub use direct::DirectLighting;
+pub use path::PathTracer;
+pub use photon::PhotonMapping;
+pub use splitting::SplittingTracer;
+
+use crate::colour::Colour;
+use crate::ray::Ray;
+use crate::scene::Scene;
+
+/// A light transport algorithm. Given a scene and a ray leaving the camera, an
+/// integrator estimates the light travelling back along that ray.
+pub trait Integrator {
+    /// return the radiance arriving at the origin of the ray, scaled by the
+    /// attenuation of the ray.
+    fn radiance(&self, scene: &Scene, ray: &Ray) -> Colour;
+}
+
+impl<T: Integrator + ?Sized> Integrator for Box<T> {
+    fn radiance(&self, scene: &Scene, ray: &Ray) -> Colour {
+        (**self).radiance(scene, ray)
+    }
+}
+
+impl<T: Integrator + ?Sized> Integrator for &T {
+    fn radiance(&self, scene: &Scene, ray: &Ray) -> Colour {
+        (*self).radiance(scene, ray)
+    }
+}
@@ -0,0 +1,62 @@
+use crate::colour::Colour;
+use crate::integrator::Integrator;
+use crate::ray::Ray;
+use crate::scene::Scene;
+use crate::shape::Direction;
+
+const SLIGHTLY_OFF_SURFACE: f64 = 0.0001;
+// brightness of a completely unoccluded surface.
+const BRIGHTNESS: f64 = 2000.0;
+
+/// Shades the first surface hit by the fraction of the hemisphere above it that
+/// is not blocked by other bodies within a given distance. Materials and
+/// lights are ignored.
+pub struct AmbientOcclusion {
+    samples: u32,
+    distance: f64,
+}
+
+impl AmbientOcclusion {
+    pub fn new(samples: u32, distance: f64) -> AmbientOcclusion {
+        AmbientOcclusion { samples, distance }
+    }
+}
+
+impl Integrator for AmbientOcclusion {
+    fn radiance(&self, scene: &Scene, ray: &Ray) -> Colour {
+        scene
+            .collision(ray)
+            .map(|(_, collision)| {
+                // face the normal towards the camera in case we hit the
+                // inside of a shape.
+                let normal = if collision.normal().dot(ray.direction()) > 0.0 {
+                    collision.normal().negate()
+                } else {
+                    collision.normal().clone()
+                };
+                let origin = collision
+                    .collision()
+                    .move_along(&normal, SLIGHTLY_OFF_SURFACE);
+
+                let unoccluded = (0..self.samples)
+                    .filter(|_| {
+                        let random = Direction::random();
+                        let direction = if random.dot(&normal) < 0.0 {
+                            random.negate()
+                        } else {
+                            random
+                        };
+                        let occlusion_ray =
+                            Ray::new(origin.clone(), direction, Colour::BLACK, ray.time());
+                        scene
+                            .collision(&occlusion_ray)
+                            .is_none_or(|(_, occluder)| occluder.t() > self.distance)
+                    })
+                    .count();
+
+                let fraction = unoccluded as f64 / self.samples as f64;
+                Colour::new(1.0, 1.0, 1.0).brighten(fraction * BRIGHTNESS)
+            })
+            .unwrap_or(Colour::BLACK)
+    }
+}
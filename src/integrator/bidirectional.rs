@@ -0,0 +1,272 @@
+use crate::body::Body;
+use crate::collision::Collision;
+use crate::colour::Colour;
+use crate::integrator::light::LightVertex;
+use crate::integrator::{Integrator, PathTracer};
+use crate::ray::Ray;
+use crate::sampler;
+use crate::scene::Scene;
+use crate::shape::{Direction, Position};
+
+const SLIGHTLY_OFF_SURFACE: f64 = 0.0001;
+// how far short of a vertex an occluder may be before the connection is
+// considered blocked. Prevents the vertex itself from blocking a connection.
+const SHADOW_TOLERANCE: f64 = 0.001;
+
+/// A bidirectional path tracer. A subpath is traced from the camera, and
+/// another from a randomly chosen light, then every vertex of one subpath is
+/// connected to every vertex of the other. This finds caustics, which a camera
+/// path only finds by chance, much faster than unidirectional tracing.
+///
+/// Only bodies with rough materials can be connected to. Perfectly smooth
+/// bodies are passed through using their own scattering. Contributions are
+/// combined with multiple importance sampling, giving each path an equal
+/// weight for every strategy that could have produced it.
+pub struct Bidirectional {
+    camera_bounces: u32,
+    light_bounces: u32,
+}
+
+/// A point on a subpath.
+struct Vertex<'s> {
+    body: &'s (dyn Body + Sync + 's),
+    collision: Collision,
+    /// the direction the ray was travelling when it arrived at the vertex.
+    incoming: Direction,
+    /// the attenuation of the ray when it arrived at the vertex.
+    throughput: Colour,
+}
+
+impl<'s> Vertex<'s> {
+    fn position(&self) -> &Position {
+        self.collision.collision()
+    }
+    fn normal(&self) -> &Direction {
+        self.collision.normal()
+    }
+    /// the bsdf at a camera vertex, scattering towards outgoing.
+    fn bsdf(&self, outgoing: &Direction) -> Option<Colour> {
+        self.body.bsdf(self.normal(), &self.incoming, outgoing)
+    }
+    /// the bsdf at a light vertex, seen by a camera ray arriving along
+    /// incoming.
+    fn light_bsdf(&self, incoming: &Direction) -> Option<Colour> {
+        self.body
+            .bsdf(self.normal(), incoming, &self.incoming.negate())
+    }
+    fn connectible(&self) -> bool {
+        self.bsdf(self.normal()).is_some()
+    }
+}
+
+impl Bidirectional {
+    pub fn new(camera_bounces: u32, light_bounces: u32) -> Bidirectional {
+        Bidirectional {
+            camera_bounces,
+            light_bounces,
+        }
+    }
+
+    /// trace a subpath, taking random numbers from the dimensions of each
+    /// bounce after first_bounce.
+    fn subpath<'s>(
+        &self,
+        scene: &'s Scene,
+        ray: Ray,
+        bounces: u32,
+        first_bounce: u32,
+    ) -> Vec<Vertex<'s>> {
+        let mut vertices = Vec::new();
+        let mut ray = ray;
+
+        for bounce in 0..bounces {
+            sampler::set_bounce(first_bounce + bounce);
+            let (body, collision) = match scene.collision(&ray) {
+                Some(hit) => hit,
+                None => break,
+            };
+            let next = PathTracer::choose_ray(body.rays(&ray, &collision, 1.0));
+            vertices.push(Vertex {
+                body,
+                collision,
+                incoming: ray.direction().clone(),
+                throughput: ray.attenuation().clone(),
+            });
+            match next {
+                Some(next) => ray = next,
+                None => break,
+            }
+        }
+        vertices
+    }
+
+    /// the geometric term between two points at the given time, or None if
+    /// something lies between them. Also returns the direction from the first
+    /// point to the second.
+    fn geometry(
+        scene: &Scene,
+        from: &Position,
+        from_normal: &Direction,
+        to: &Position,
+        to_normal: &Direction,
+        time: f64,
+    ) -> Option<(Direction, f64)> {
+        let difference = Direction::from_two_points(from, to);
+        let distance = difference.len();
+        let direction = difference.normalise();
+
+        let origin = from.move_along(&direction, SLIGHTLY_OFF_SURFACE);
+        let shadow_ray = Ray::new(origin, direction.clone(), Colour::BLACK, time);
+        if let Some((_, occluder)) = scene.collision(&shadow_ray) {
+            if occluder.t() < distance - SHADOW_TOLERANCE {
+                return None;
+            }
+        }
+
+        let geometry = from_normal.dot(&direction).abs() * to_normal.dot(&direction).abs()
+            / (distance * distance);
+        Some((direction, geometry))
+    }
+
+    /// The weight given to a path. path holds whether each vertex can be
+    /// connected to, starting at the light. light_sampled is true if the
+    /// first vertex lies on a light that can be chosen by a light subpath.
+    fn weight(&self, path: &[bool], light_sampled: bool) -> f64 {
+        let length = path.len();
+        // the camera subpath alone can always produce a path ending at a
+        // light, provided that it is short enough.
+        let mut strategies = if length <= self.camera_bounces as usize {
+            1
+        } else {
+            0
+        };
+        if light_sampled {
+            strategies += (1..length)
+                .filter(|&light_length| {
+                    light_length <= self.light_bounces as usize + 1
+                        && length - light_length <= self.camera_bounces as usize
+                        && path[light_length - 1]
+                        && path[light_length]
+                })
+                .count();
+        }
+        1.0 / strategies.max(1) as f64
+    }
+
+    fn connect_light(
+        scene: &Scene,
+        camera: &Vertex,
+        light: &LightVertex,
+        time: f64,
+    ) -> Option<Colour> {
+        let (direction, geometry) = Bidirectional::geometry(
+            scene,
+            camera.position(),
+            camera.normal(),
+            &light.sample.position,
+            &light.sample.normal,
+            time,
+        )?;
+        // lights only emit from the outside of their surface.
+        if light.sample.normal.dot(&direction) >= 0.0 {
+            return None;
+        }
+        let bsdf = camera.bsdf(&direction)?;
+        Some(
+            camera
+                .throughput
+                .brighten_colour(&bsdf)
+                .brighten_colour(&light.throughput)
+                .brighten(geometry),
+        )
+    }
+
+    fn connect(scene: &Scene, camera: &Vertex, light: &Vertex, time: f64) -> Option<Colour> {
+        let (direction, geometry) = Bidirectional::geometry(
+            scene,
+            camera.position(),
+            camera.normal(),
+            light.position(),
+            light.normal(),
+            time,
+        )?;
+        let camera_bsdf = camera.bsdf(&direction)?;
+        let light_bsdf = light.light_bsdf(&direction)?;
+        Some(
+            camera
+                .throughput
+                .brighten_colour(&camera_bsdf)
+                .brighten_colour(&light_bsdf)
+                .brighten_colour(&light.throughput)
+                .brighten(geometry),
+        )
+    }
+}
+
+impl Integrator for Bidirectional {
+    fn radiance(&self, scene: &Scene, ray: &Ray) -> Colour {
+        let camera_path = self.subpath(scene, ray.clone(), self.camera_bounces, 0);
+        // the light subpath takes its random numbers from the dimensions after
+        // the camera subpath's, so the two aren't correlated.
+        sampler::set_bounce(self.camera_bounces);
+        let light = LightVertex::sample(scene);
+        let light_path = match &light {
+            Some(light) => self.subpath(
+                scene,
+                light.emit(ray.time()),
+                self.light_bounces,
+                self.camera_bounces + 1,
+            ),
+            None => Vec::new(),
+        };
+
+        let mut colour = Colour::BLACK;
+        for (index, camera) in camera_path.iter().enumerate() {
+            let camera_side = || {
+                camera_path[..=index]
+                    .iter()
+                    .rev()
+                    .map(|vertex| vertex.connectible())
+            };
+
+            // the camera subpath hit a light by itself.
+            if camera.body.is_light() {
+                let light_sampled = camera.body.sample_surface().is_some();
+                let path: Vec<bool> = std::iter::once(light_sampled)
+                    .chain(camera_side().skip(1))
+                    .collect();
+                let emission = camera.body.emission().brighten_colour(&camera.throughput);
+                colour = &colour + &emission.brighten(self.weight(&path, light_sampled));
+            }
+
+            if !camera.connectible() {
+                continue;
+            }
+
+            if let Some(light) = &light {
+                if let Some(contribution) =
+                    Bidirectional::connect_light(scene, camera, light, ray.time())
+                {
+                    let path: Vec<bool> = std::iter::once(true).chain(camera_side()).collect();
+                    colour = &colour + &contribution.brighten(self.weight(&path, true));
+                }
+            }
+
+            for (light_index, light_vertex) in light_path.iter().enumerate() {
+                if !light_vertex.connectible() {
+                    continue;
+                }
+                if let Some(contribution) =
+                    Bidirectional::connect(scene, camera, light_vertex, ray.time())
+                {
+                    let path: Vec<bool> = std::iter::once(true)
+                        .chain(light_path[..=light_index].iter().map(|v| v.connectible()))
+                        .chain(camera_side())
+                        .collect();
+                    colour = &colour + &contribution.brighten(self.weight(&path, true));
+                }
+            }
+        }
+        colour
+    }
+}
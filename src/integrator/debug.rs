@@ -0,0 +1,62 @@
+//! Integrators which visualise geometry rather than light. Useful for checking
+//! that shapes and normals are correct without waiting for a full render.
+
+use crate::colour::Colour;
+use crate::integrator::Integrator;
+use crate::ray::Ray;
+use crate::scene::Scene;
+
+/// Undo the tone mapping performed by `Colour::to_bytes` so that a value
+/// between 0.0 and 1.0 appears linearly in the output image.
+fn display(value: f64) -> f64 {
+    let value = value.clamp(0.0, 0.999);
+    200.0 * value / (1.0 - value)
+}
+
+fn display_colour(red: f64, green: f64, blue: f64) -> Colour {
+    Colour::new(display(red), display(green), display(blue))
+}
+
+/// Colour each surface by its normal. The x, y and z components map to red,
+/// green and blue.
+pub struct Normals;
+
+impl Integrator for Normals {
+    fn radiance(&self, scene: &Scene, ray: &Ray) -> Colour {
+        scene
+            .collision(ray)
+            .map(|(_, collision)| {
+                let normal = collision.normal();
+                display_colour(
+                    (normal.x() + 1.0) / 2.0,
+                    (normal.y() + 1.0) / 2.0,
+                    (normal.z() + 1.0) / 2.0,
+                )
+            })
+            .unwrap_or(Colour::BLACK)
+    }
+}
+
+/// Colour each surface by its distance from the camera. Close surfaces are
+/// white, surfaces at or beyond max_distance are black.
+pub struct Depth {
+    max_distance: f64,
+}
+
+impl Depth {
+    pub fn new(max_distance: f64) -> Depth {
+        Depth { max_distance }
+    }
+}
+
+impl Integrator for Depth {
+    fn radiance(&self, scene: &Scene, ray: &Ray) -> Colour {
+        scene
+            .collision(ray)
+            .map(|(_, collision)| {
+                let shade = 1.0 - collision.t() / self.max_distance;
+                display_colour(shade, shade, shade)
+            })
+            .unwrap_or(Colour::BLACK)
+    }
+}
@@ -0,0 +1,35 @@
+use crate::colour::Colour;
+use crate::integrator::Integrator;
+use crate::ray::Ray;
+use crate::scene::Scene;
+
+/// A fast preview integrator. Light is only gathered from emissive bodies
+/// that are visible from the first surface hit, there is no indirect
+/// lighting.
+pub struct DirectLighting {
+    samples: u32,
+}
+
+impl DirectLighting {
+    /// samples is the maximum number of rays cast from a rough surface.
+    pub fn new(samples: u32) -> DirectLighting {
+        DirectLighting { samples }
+    }
+}
+
+impl Integrator for DirectLighting {
+    fn radiance(&self, scene: &Scene, ray: &Ray) -> Colour {
+        scene
+            .collision(ray)
+            .map(|(body, collision)| {
+                let mut colour = body.emission().brighten_colour(ray.attenuation());
+                for ray in body.rays(ray, &collision, self.samples as f64) {
+                    if let Some((light, _)) = scene.collision(&ray) {
+                        colour = &colour + &light.emission().brighten_colour(ray.attenuation());
+                    }
+                }
+                colour
+            })
+            .unwrap_or(Colour::BLACK)
+    }
+}
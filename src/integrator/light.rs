@@ -0,0 +1,57 @@
+use crate::colour::Colour;
+use crate::ray::Ray;
+use crate::sampler;
+use crate::scene::Scene;
+use crate::shape::{Direction, SurfaceSample};
+use std::f64::consts::PI;
+
+const SLIGHTLY_OFF_SURFACE: f64 = 0.0001;
+
+/// A point chosen at random on the surface of one of the lights in a scene.
+pub struct LightVertex {
+    pub sample: SurfaceSample,
+    /// emitted radiance divided by the probability of choosing this point.
+    pub throughput: Colour,
+}
+
+impl LightVertex {
+    /// choose a light uniformly, then a point on its surface. Returns None if
+    /// the scene has no lights which can be sampled.
+    pub fn sample(scene: &Scene) -> Option<LightVertex> {
+        let lights: Vec<(Colour, SurfaceSample)> = scene
+            .bodies()
+            .iter()
+            .filter(|body| body.is_light())
+            .filter_map(|body| {
+                body.sample_surface()
+                    .map(|sample| (body.emission(), sample))
+            })
+            .collect();
+        if lights.is_empty() {
+            return None;
+        }
+
+        let light_count = lights.len();
+        let index = ((sampler::next() * light_count as f64) as usize).min(light_count - 1);
+        lights
+            .into_iter()
+            .nth(index)
+            .map(|(emission, sample)| LightVertex {
+                throughput: emission.brighten(light_count as f64 / sample.pdf),
+                sample,
+            })
+    }
+
+    /// cast a ray from the light in a cosine weighted direction.
+    pub fn emit(&self, time: f64) -> Ray {
+        let normal = &self.sample.normal;
+        let direction = (&normal.to_position() + &Direction::random().to_position())
+            .to_direction()
+            .normalise();
+        let origin = self
+            .sample
+            .position
+            .move_along(normal, SLIGHTLY_OFF_SURFACE);
+        Ray::new(origin, direction, self.throughput.brighten(PI), time)
+    }
+}
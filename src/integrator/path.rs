@@ -0,0 +1,65 @@
+use crate::colour::Colour;
+use crate::integrator::Integrator;
+use crate::ray::Ray;
+use crate::sampler;
+use crate::scene::Scene;
+
+/// A unidirectional path tracer. Only a single ray is followed at each bounce,
+/// so many samples per pixel are required, but each sample is cheap.
+pub struct PathTracer {
+    bounces: u32,
+}
+
+impl PathTracer {
+    pub fn new(bounces: u32) -> PathTracer {
+        PathTracer { bounces }
+    }
+
+    /// choose one of the rays cast from a surface, with probability
+    /// proportional to its attenuation. The chosen ray is brightened to
+    /// account for the rays not taken.
+    pub fn choose_ray(rays: Vec<Ray>) -> Option<Ray> {
+        let weight = |ray: &Ray| {
+            let att = ray.attenuation();
+            att.red() + att.green() + att.blue()
+        };
+        let total: f64 = rays.iter().map(weight).sum();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut choice = sampler::next() * total;
+        let last = rays.len() - 1;
+        rays.into_iter().enumerate().find_map(|(index, ray)| {
+            let ray_weight = weight(&ray);
+            choice -= ray_weight;
+            if (choice < 0.0 || index == last) && ray_weight > 0.0 {
+                Some(ray.attenuate_num(ray_weight / total))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+impl Integrator for PathTracer {
+    fn radiance(&self, scene: &Scene, ray: &Ray) -> Colour {
+        let mut colour = Colour::BLACK;
+        let mut ray = ray.clone();
+
+        for bounce in 0..self.bounces {
+            sampler::set_bounce(bounce);
+            let (body, collision) = match scene.collision(&ray) {
+                Some(hit) => hit,
+                None => break,
+            };
+            colour = &colour + &body.emission().brighten_colour(ray.attenuation());
+
+            match PathTracer::choose_ray(body.rays(&ray, &collision, 1.0)) {
+                Some(next) => ray = next,
+                None => break,
+            }
+        }
+        colour
+    }
+}
@@ -0,0 +1,153 @@
+mod kdtree;
+
+use crate::colour::Colour;
+use crate::integrator::light::LightVertex;
+use crate::integrator::{Integrator, PathTracer};
+use crate::ray::Ray;
+use crate::scene::Scene;
+use crate::shape::{Direction, Position};
+use kdtree::{KdTree, Located};
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::f64::consts::PI;
+
+/// A packet of light which has arrived at a rough surface.
+struct Photon {
+    position: Position,
+    /// the direction the photon was travelling when it arrived.
+    incoming: Direction,
+    power: Colour,
+}
+
+impl Located for Photon {
+    fn position(&self) -> &Position {
+        &self.position
+    }
+}
+
+/// A photon mapper. Before rendering, photons are traced from the lights
+/// through the scene and stored wherever they land on a rough surface. Camera
+/// rays pass through smooth bodies until they hit a rough surface, then the
+/// light there is estimated from the density of nearby photons.
+///
+/// Light focused by glass onto a rough surface is found directly, so caustics
+/// are smooth even at low sample counts. The estimate is biased, and appears
+/// blurry if too few photons are traced.
+pub struct PhotonMapping {
+    photons: KdTree<Photon>,
+    neighbours: usize,
+    max_radius: f64,
+    bounces: u32,
+}
+
+impl PhotonMapping {
+    /// trace photon_count photons from the lights of a scene. The light at a
+    /// point is estimated from up to the given number of neighbouring photons
+    /// within max_radius of the point.
+    pub fn new(
+        scene: &Scene,
+        photon_count: u32,
+        neighbours: usize,
+        max_radius: f64,
+        bounces: u32,
+    ) -> PhotonMapping {
+        let photons = (0..photon_count)
+            .into_par_iter()
+            .map(|_| PhotonMapping::trace_photon(scene, bounces))
+            .flatten()
+            .map(|photon| Photon {
+                power: photon.power.brighten(1.0 / photon_count as f64),
+                ..photon
+            })
+            .collect();
+
+        PhotonMapping {
+            photons: KdTree::new(photons),
+            neighbours,
+            max_radius,
+            bounces,
+        }
+    }
+
+    fn trace_photon(scene: &Scene, bounces: u32) -> Vec<Photon> {
+        let mut photons = Vec::new();
+        let mut ray = match LightVertex::sample(scene) {
+            Some(light) => light.emit(scene.sample_time()),
+            None => return photons,
+        };
+
+        for _ in 0..bounces {
+            let (body, collision) = match scene.collision(&ray) {
+                Some(hit) => hit,
+                None => break,
+            };
+            if body
+                .bsdf(collision.normal(), ray.direction(), collision.normal())
+                .is_some()
+            {
+                photons.push(Photon {
+                    position: collision.collision().clone(),
+                    incoming: ray.direction().clone(),
+                    power: ray.attenuation().clone(),
+                });
+            }
+            match PathTracer::choose_ray(body.rays(&ray, &collision, 1.0)) {
+                Some(next) => ray = next,
+                None => break,
+            }
+        }
+        photons
+    }
+}
+
+impl Integrator for PhotonMapping {
+    fn radiance(&self, scene: &Scene, ray: &Ray) -> Colour {
+        let mut colour = Colour::BLACK;
+        let mut ray = ray.clone();
+
+        for _ in 0..self.bounces {
+            let (body, collision) = match scene.collision(&ray) {
+                Some(hit) => hit,
+                None => break,
+            };
+            colour = &colour + &body.emission().brighten_colour(ray.attenuation());
+
+            let normal = collision.normal();
+            if body.bsdf(normal, ray.direction(), normal).is_some() {
+                let neighbours =
+                    self.photons
+                        .nearest(collision.collision(), self.neighbours, self.max_radius);
+                // if there are not enough photons nearby, the furthest photon
+                // found gives a poor estimate of the area they cover.
+                let radius_sq = if neighbours.len() < self.neighbours {
+                    self.max_radius * self.max_radius
+                } else {
+                    neighbours
+                        .iter()
+                        .map(|(dist_sq, _)| *dist_sq)
+                        .fold(0.0, f64::max)
+                };
+                if radius_sq <= 0.0 {
+                    break;
+                }
+
+                let mut flux = Colour::BLACK;
+                for (_, photon) in neighbours {
+                    let outgoing = photon.incoming.negate();
+                    if let Some(bsdf) = body.bsdf(normal, ray.direction(), &outgoing) {
+                        flux = &flux + &photon.power.brighten_colour(&bsdf);
+                    }
+                }
+                let estimate = flux.brighten(1.0 / (PI * radius_sq));
+                colour = &colour + &estimate.brighten_colour(ray.attenuation());
+                break;
+            }
+
+            match PathTracer::choose_ray(body.rays(&ray, &collision, 1.0)) {
+                Some(next) => ray = next,
+                None => break,
+            }
+        }
+        colour
+    }
+}
@@ -0,0 +1,146 @@
+use crate::shape::Position;
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Anything with a position that can be stored in a kd-tree.
+pub trait Located {
+    fn position(&self) -> &Position;
+}
+
+fn coordinate(position: &Position, axis: usize) -> f64 {
+    match axis {
+        0 => position.x(),
+        1 => position.y(),
+        _ => position.z(),
+    }
+}
+
+/// A balanced kd-tree stored implicitly in a vector. The median of every range
+/// is the node splitting that range, and the axis of the split cycles through
+/// x, y and z with depth.
+pub struct KdTree<T> {
+    items: Vec<T>,
+}
+
+struct Query<'p> {
+    position: &'p Position,
+    count: usize,
+    max_dist_sq: f64,
+}
+
+struct Neighbour {
+    dist_sq: f64,
+    index: usize,
+}
+
+impl PartialEq for Neighbour {
+    fn eq(&self, other: &Neighbour) -> bool {
+        self.dist_sq == other.dist_sq
+    }
+}
+impl Eq for Neighbour {}
+impl PartialOrd for Neighbour {
+    fn partial_cmp(&self, other: &Neighbour) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Neighbour {
+    fn cmp(&self, other: &Neighbour) -> Ordering {
+        self.dist_sq
+            .partial_cmp(&other.dist_sq)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+impl<T: Located> KdTree<T> {
+    pub fn new(mut items: Vec<T>) -> KdTree<T> {
+        KdTree::build(&mut items[..], 0);
+        KdTree { items }
+    }
+
+    fn build(items: &mut [T], axis: usize) {
+        if items.len() <= 1 {
+            return;
+        }
+        let median = items.len() / 2;
+        items.select_nth_unstable_by(median, |item1, item2| {
+            coordinate(item1.position(), axis)
+                .partial_cmp(&coordinate(item2.position(), axis))
+                .unwrap_or(Ordering::Equal)
+        });
+        let (lower, upper) = items.split_at_mut(median);
+        KdTree::build(lower, (axis + 1) % 3);
+        KdTree::build(&mut upper[1..], (axis + 1) % 3);
+    }
+
+    /// find up to count items closest to a position, no further away than
+    /// max_distance. Returns the items along with their squared distance.
+    pub fn nearest(&self, position: &Position, count: usize, max_distance: f64) -> Vec<(f64, &T)> {
+        let query = Query {
+            position,
+            count,
+            max_dist_sq: max_distance * max_distance,
+        };
+        let mut heap = BinaryHeap::new();
+        self.search(&query, 0, self.items.len(), 0, &mut heap);
+        heap.into_iter()
+            .map(|neighbour| (neighbour.dist_sq, &self.items[neighbour.index]))
+            .collect()
+    }
+
+    fn search(
+        &self,
+        query: &Query,
+        start: usize,
+        end: usize,
+        axis: usize,
+        heap: &mut BinaryHeap<Neighbour>,
+    ) {
+        if start >= end || query.count == 0 {
+            return;
+        }
+        let median = start + (end - start) / 2;
+        let node = &self.items[median];
+
+        let dist_sq = node.position().dist_sq(query.position);
+        if dist_sq < query.max_dist_sq {
+            if heap.len() < query.count {
+                heap.push(Neighbour {
+                    dist_sq,
+                    index: median,
+                });
+            } else if heap
+                .peek()
+                .is_some_and(|furthest| dist_sq < furthest.dist_sq)
+            {
+                heap.pop();
+                heap.push(Neighbour {
+                    dist_sq,
+                    index: median,
+                });
+            }
+        }
+
+        // search the side of the split containing the position first, then
+        // the other side only if it could hold a closer item.
+        let offset = coordinate(query.position, axis) - coordinate(node.position(), axis);
+        let next_axis = (axis + 1) % 3;
+        let (near, far) = if offset < 0.0 {
+            ((start, median), (median + 1, end))
+        } else {
+            ((median + 1, end), (start, median))
+        };
+        self.search(query, near.0, near.1, next_axis, heap);
+
+        let radius_sq = if heap.len() < query.count {
+            query.max_dist_sq
+        } else {
+            heap.peek()
+                .map_or(query.max_dist_sq, |furthest| furthest.dist_sq)
+        };
+        if offset * offset < radius_sq {
+            self.search(query, far.0, far.1, next_axis, heap);
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use crate::colour::Colour;
+use crate::integrator::Integrator;
+use crate::ray::Ray;
+use crate::sampler;
+use crate::scene::Scene;
+
+// if the attenuation is low, then the resulting pixel will be largely affected
+// by the colour, for high attenuation, barely any effect. We should therefore
+// cast more rays if the light is more effective to spend more computational
+// power where it is required. this value is chosen arbitrarily to approximate
+// even levels of detail.
+const EFFICACY_CONSTANT: f64 = 40.0;
+
+/// A tracer which splits each ray into several at every bounce. The number of
+/// rays cast depends on how much the ray is expected to contribute to the
+/// final pixel.
+pub struct SplittingTracer {
+    bounces: u32,
+}
+
+impl SplittingTracer {
+    pub fn new(bounces: u32) -> SplittingTracer {
+        SplittingTracer { bounces }
+    }
+
+    fn sampler(&self, scene: &Scene, ray: &Ray, bounce: u32) -> Colour {
+        if bounce >= self.bounces {
+            return Colour::BLACK;
+        }
+        sampler::set_bounce(bounce);
+
+        scene
+            .collision(ray)
+            .map(|(body, collision)| {
+                let att = ray.attenuation();
+                // efficacy is how much a given ray is expected to affect the pixel.
+                let efficacy = 1.0 + EFFICACY_CONSTANT * (att.red() + att.blue() + att.green());
+                let rays = body.rays(ray, &collision, efficacy);
+
+                let mut colour = body.emission().brighten_colour(att);
+                for ray in rays {
+                    colour = &colour + &self.sampler(scene, &ray, bounce + 1);
+                }
+                colour
+            })
+            .unwrap_or(Colour::BLACK)
+    }
+}
+
+impl Integrator for SplittingTracer {
+    fn radiance(&self, scene: &Scene, ray: &Ray) -> Colour {
+        self.sampler(scene, ray, 0)
+    }
+}
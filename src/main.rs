@@ -1,32 +1,190 @@
+#[macro_use]
+extern crate nom;
+
+mod animation;
+mod aov;
 mod body;
 mod camera;
 mod collision;
 pub mod colour;
+mod denoise;
+mod filter;
+mod image;
+mod integrator;
 mod material;
 mod ray;
+mod sampler;
 mod scene;
 pub mod shape;
 pub mod vec3;
 // mod monad;
 
+use std::env;
 use std::io;
 use std::io::Write;
+use std::path::Path;
 
+use animation::{Interpolation, Track};
+use aov::Aov;
 use body::{BasicBody, Body};
-use camera::Camera;
+use camera::{
+    Animated, Aperture, Cubemap, Equirectangular, Eye, Fisheye, Lens, Moving, OmniStereo,
+    Orthographic, Perspective, Shutter, SideBySide, Stereo,
+};
 use colour::Colour;
+use denoise::Denoiser;
+use filter::Filter;
+use integrator::{
+    AmbientOcclusion, Bidirectional, DirectLighting, Integrator, PathTracer, PhotonMapping,
+    SplittingTracer,
+};
 use material::Physical;
+use nalgebra::base::Matrix4;
+use sampler::Sampler;
 use scene::Scene;
 use shape::march;
-use shape::{Cuboid, Difference, Direction, Plane, Position, Shape, Sphere, Translate, Union};
+use shape::patch;
+use shape::{
+    Ball, Bounds, Capsule, Cone, Cuboid, Cylinder, Difference, Direction, Disc, Fractal,
+    Heightfield, Implicit, Intersection, Metaballs, MovingAffine, MovingTranslate, Patch, Plane,
+    Polygon, Position, Quad, Quadric, Shape, Sphere, Torus, Translate, Union,
+};
+
+/// the number of frames rendered for each second of an animation.
+const FRAMES_PER_SECOND: f64 = 24.0;
 
 fn main() -> io::Result<()> {
-    let camera = Camera::new(
+    let lens = Lens {
+        aperture: Aperture::polygon(0.5, 6, 0.0),
+        focus_distance: 66.0,
+        squeeze: 1.0,
+    };
+    let _lens = Lens {
+        aperture: Aperture::from_f_stop(2.0, 2.8),
+        focus_distance: 66.0,
+        squeeze: 2.0,
+    };
+    let _lens = Lens {
+        aperture: Aperture::Circle { radius: 0.5 },
+        focus_distance: 66.0,
+        squeeze: 1.0,
+    };
+    let _lens = Lens {
+        aperture: Aperture::Pinhole,
+        focus_distance: 1.0,
+        squeeze: 1.0,
+    };
+
+    let camera = Perspective::new(
         Position::new(-20.0, -60.0, 40.0),
         Position::new(0.0, 0.0, 20.0),
         1.2, // size, x, y
         1.2,
-        1.0, // aperture size
+        lens.clone(),
+        Direction::UP,
+        256, // resolution
+        256,
+    );
+    let _camera = Orthographic::new(
+        Position::new(-20.0, -60.0, 40.0),
+        Position::new(0.0, 0.0, 20.0),
+        80.0, // size, x, y
+        80.0,
+        Direction::UP,
+        256, // resolution
+        256,
+    );
+    let _camera = Fisheye::new(
+        Position::new(-20.0, -60.0, 40.0),
+        Position::new(0.0, 0.0, 20.0),
+        std::f64::consts::PI, // field of view
+        Direction::UP,
+        256, // resolution
+        256,
+    );
+    let _camera = Equirectangular::new(
+        Position::new(0.0, -40.0, 20.0),
+        Position::new(0.0, 0.0, 20.0),
+        Direction::UP,
+        512, // resolution
+        256,
+    );
+    let _camera = Cubemap::new(
+        Position::new(0.0, -40.0, 20.0),
+        Position::new(0.0, 0.0, 20.0),
+        Direction::UP,
+        128, // face resolution
+    );
+    let _camera = Shutter::new(
+        0.0, // shutter open, close
+        1.0,
+        Moving::new(
+            Track::new(
+                Interpolation::Linear,
+                vec![(0.0, Position::ORIGIN), (1.0, Position::new(2.0, 0.0, 0.0))],
+            ),
+            Perspective::new(
+                Position::new(-20.0, -60.0, 40.0),
+                Position::new(0.0, 0.0, 20.0),
+                1.2, // size, x, y
+                1.2,
+                lens.clone(),
+                Direction::UP,
+                256, // resolution
+                256,
+            ),
+        ),
+    );
+    // a turntable, circling the scene once every four seconds.
+    let turntable_lens = lens.clone();
+    let _camera = Animated::new(
+        Track::new(
+            Interpolation::Bezier,
+            (0..=8)
+                .map(|index| {
+                    let angle = index as f64 * std::f64::consts::PI / 4.0;
+                    let position = Position::new(60.0 * angle.sin(), -60.0 * angle.cos(), 40.0);
+                    (index as f64 * 0.5, position)
+                })
+                .collect(),
+        ),
+        Track::constant(Position::new(0.0, 0.0, 20.0)),
+        move |position, focal_point| {
+            Perspective::new(
+                position,
+                focal_point,
+                1.2, // size, x, y
+                1.2,
+                turntable_lens.clone(),
+                Direction::UP,
+                256, // resolution
+                256,
+            )
+        },
+    );
+    let _camera = Perspective::from_vertical_fov(
+        Position::new(-20.0, -60.0, 40.0),
+        Position::new(0.0, 0.0, 20.0),
+        1.0, // field of view
+        lens.clone(),
+        Direction::UP,
+        256, // resolution
+        256,
+    );
+    let _camera = Perspective::from_horizontal_fov(
+        Position::new(-20.0, -60.0, 40.0),
+        Position::new(0.0, 0.0, 20.0),
+        1.0, // field of view
+        lens.clone(),
+        Direction::UP,
+        256, // resolution
+        256,
+    );
+    let _camera = Perspective::from_focal_length(
+        Position::new(-20.0, -60.0, 40.0),
+        Position::new(0.0, 0.0, 20.0),
+        35.0, // focal length in mm
+        lens.clone(),
         Direction::UP,
         256, // resolution
         256,
@@ -69,24 +227,307 @@ fn main() -> io::Result<()> {
         refractive_sharpness: None,
     };
 
+    let march_settings = march::MarchSettings {
+        epsilon: 0.00001,
+        normal_epsilon: 0.001,
+        relative_epsilon: 0.0005,
+        draw_distance: 200.0,
+        max_steps: 1000,
+        relaxation: 1.2,
+    };
     let _shapes2 = Translate::new(
         Position::new(0.0, 0.0, 15.0),
-        march::MarchShape(march::sphere::Sphere::new(10.0)),
+        march::MarchShape::new(march::sphere::Sphere::new(10.0), march_settings),
     );
     let _shapes2 = Translate::new(Position::new(0.0, 0.0, 15.0), Sphere::new(10.0));
 
     let shapes2 = Translate::new(
         Position::new(0.0, 0.0, 15.0),
-        march::MarchShape(march::cuboid::Cuboid::new(20.0, 10.0, 14.0)),
+        march::MarchShape::new(march::cuboid::Cuboid::new(20.0, 10.0, 14.0), march_settings),
     );
     let _shapes2 = Translate::new(Position::new(0.0, 0.0, 15.0), Cuboid::new(20.0, 10.0, 14.0));
+    // a twisted pillar between two arches of beads.
+    let _shapes2 = Translate::new(
+        Position::new(0.0, 0.0, 12.0),
+        march::MarchShape::new(
+            march::combine::Union::new(
+                march::domain::Twist::new(
+                    march::cuboid::Cuboid::new(8.0, 8.0, 24.0),
+                    0.15, // radians per unit
+                    4.0 * 2f64.sqrt(),
+                ),
+                march::domain::Mirror::new(
+                    march::domain::Affine::new(
+                        march::domain::Bend::new(
+                            march::domain::RepeatFinite::new(
+                                march::sphere::Sphere::new(1.5),
+                                [4.0, 0.0, 0.0],
+                                [7, 1, 1],
+                            ),
+                            -0.08, // curvature
+                            1.5,
+                        ),
+                        Matrix4::new_translation(&nalgebra::Vector3::new(0.0, 7.0, 4.0)),
+                    ),
+                    Direction::FORWARDS,
+                ),
+            ),
+            march_settings,
+        ),
+    );
+    // escape time and folding fractals, scaled up from their natural size.
+    let _shapes2 = march::MarchShape::new(
+        march::domain::Affine::new(
+            march::mandelbulb::Mandelbulb::new(8.0, 12),
+            Matrix4::new_translation(&nalgebra::Vector3::new(0.0, 0.0, 12.0))
+                * Matrix4::new_scaling(10.0),
+        ),
+        march_settings,
+    );
+    let _shapes2 = march::MarchShape::new(
+        march::domain::Affine::new(
+            march::mandelbox::Mandelbox::new(-1.5, 1.0, 0.5, 1.0, 15),
+            Matrix4::new_translation(&nalgebra::Vector3::new(0.0, 0.0, 12.0))
+                * Matrix4::new_scaling(5.0),
+        ),
+        march_settings,
+    );
+    let _shapes2 = Translate::new(
+        Position::new(0.0, 0.0, 12.0),
+        march::MarchShape::new(march::menger::Menger::new(20.0, 4), march_settings),
+    );
+    let _shapes2 = Translate::new(
+        Position::new(0.0, 0.0, 12.0),
+        march::MarchShape::new(march::sierpinski::Sierpinski::new(20.0, 6), march_settings),
+    );
+    // a rounded box melted into a rippled sphere, with a hollow ball cut
+    // out of the middle.
+    let _shapes2 = Translate::new(
+        Position::new(0.0, 0.0, 15.0),
+        march::MarchShape::new(
+            march::combine::Subtraction::new(
+                march::combine::SmoothUnion::new(
+                    march::combine::Round::new(march::cuboid::Cuboid::new(16.0, 16.0, 6.0), 2.0),
+                    march::combine::Displace::new(
+                        march::sphere::Sphere::new(8.0),
+                        |point: &Position| 0.5 * (point.x() * 1.5).sin() * (point.y() * 1.5).sin(),
+                        0.75 * 2f64.sqrt(), // lipschitz
+                    ),
+                    4.0, // blend radius
+                ),
+                march::combine::Onion::new(march::sphere::Sphere::new(5.0), 1.0),
+            ),
+            march_settings,
+        ),
+    );
 
     let _shapes2 = Translate::new(
         Position::new(0.0, 0.0, 15.0),
         Difference::new(Cuboid::new(20.0, 20.0, 20.0), Sphere::new(13.0)),
     );
 
-    let ground = Plane::new();
+    let _shapes2 = Translate::new(Position::new(0.0, 0.0, 15.0), Cylinder::new(8.0, 20.0));
+    let _shapes2 = Translate::new(Position::new(0.0, 0.0, 15.0), Cone::new(10.0, 20.0));
+    let _shapes2 = Translate::new(Position::new(0.0, 0.0, 15.0), Capsule::new(6.0, 12.0));
+    let _shapes2 = Translate::new(
+        Position::new(0.0, 0.0, 15.0),
+        Difference::new(Cylinder::new(10.0, 20.0), Capsule::new(6.0, 12.0)),
+    );
+    let _shapes2 = Translate::new(Position::new(0.0, 0.0, 25.0), Disc::new(10.0));
+    let _shapes2 = Translate::new(Position::new(0.0, 0.0, 15.0), Torus::new(9.0, 4.0));
+    let _shapes2 = Translate::new(
+        Position::new(0.0, 0.0, 15.0),
+        Quadric::ellipsoid(12.0, 6.0, 9.0),
+    );
+    let _shapes2 = Translate::new(
+        Position::new(0.0, 0.0, 15.0),
+        Intersection::new(
+            Quadric::hyperboloid(5.0, 5.0, 8.0),
+            Cuboid::new(30.0, 30.0, 20.0),
+        ),
+    );
+    // a standing panel, and a five pointed star.
+    let _shapes2 = Quad::new(
+        Position::new(-10.0, 0.0, 5.0),
+        Direction::new(20.0, 0.0, 0.0),
+        Direction::new(0.0, 5.0, 20.0),
+    );
+    let _shapes2 = Polygon::new(
+        (0..10)
+            .map(|index| {
+                let angle = index as f64 * std::f64::consts::PI / 5.0;
+                let radius = if index % 2 == 0 { 12.0 } else { 5.0 };
+                Position::new(radius * angle.sin(), 0.0, 15.0 + radius * angle.cos())
+            })
+            .collect(),
+    );
+    // a saddle shaped Bézier patch, and a NURBS surface with a bump pulled
+    // up by the heavy control point in the middle.
+    let _shapes2 = Translate::new(
+        Position::new(0.0, 0.0, 15.0),
+        Patch::new(
+            patch::bezier::Bezier::new([
+                [
+                    Position::new(-15.0, -15.0, 8.0),
+                    Position::new(-5.0, -15.0, 0.0),
+                    Position::new(5.0, -15.0, 0.0),
+                    Position::new(15.0, -15.0, 8.0),
+                ],
+                [
+                    Position::new(-15.0, -5.0, 0.0),
+                    Position::new(-5.0, -5.0, -4.0),
+                    Position::new(5.0, -5.0, -4.0),
+                    Position::new(15.0, -5.0, 0.0),
+                ],
+                [
+                    Position::new(-15.0, 5.0, 0.0),
+                    Position::new(-5.0, 5.0, -4.0),
+                    Position::new(5.0, 5.0, -4.0),
+                    Position::new(15.0, 5.0, 0.0),
+                ],
+                [
+                    Position::new(-15.0, 15.0, 8.0),
+                    Position::new(-5.0, 15.0, 0.0),
+                    Position::new(5.0, 15.0, 0.0),
+                    Position::new(15.0, 15.0, 8.0),
+                ],
+            ]),
+            16, // subdivisions
+        ),
+    );
+    let _shapes2 = Translate::new(
+        Position::new(0.0, 0.0, 10.0),
+        Patch::new(
+            patch::nurbs::Nurbs::new(
+                (0..5)
+                    .map(|row| {
+                        (0..5)
+                            .map(|column| {
+                                let weight = if row == 2 && column == 2 { 4.0 } else { 1.0 };
+                                let height = if row == 2 && column == 2 { 15.0 } else { 0.0 };
+                                let position = Position::new(
+                                    (column as f64 - 2.0) * 8.0,
+                                    (row as f64 - 2.0) * 8.0,
+                                    height,
+                                );
+                                (position, weight)
+                            })
+                            .collect()
+                    })
+                    .collect(),
+                patch::nurbs::Nurbs::clamped_knots(5, 3),
+                patch::nurbs::Nurbs::clamped_knots(5, 3),
+                3, // degree along u and v
+                3,
+            ),
+            16, // subdivisions
+        ),
+    );
+    // a curved mirror, cut off from the infinite paraboloid.
+    let _shapes2 = Translate::new(
+        Position::new(0.0, 0.0, 5.0),
+        Intersection::new(Quadric::paraboloid(4.0), Cuboid::new(30.0, 30.0, 30.0)),
+    );
+
+    // moving shapes are blurred when rendered with a camera with an open
+    // shutter.
+    // blobs of liquid merging together, with a hollow pushed into the top.
+    let _shapes2 = Translate::new(
+        Position::new(0.0, 0.0, 12.0),
+        Metaballs::new(
+            vec![
+                Ball {
+                    centre: Position::new(-6.0, 0.0, 0.0),
+                    radius: 12.0,
+                    weight: 1.0,
+                },
+                Ball {
+                    centre: Position::new(6.0, 2.0, 0.0),
+                    radius: 10.0,
+                    weight: 1.0,
+                },
+                Ball {
+                    centre: Position::new(0.0, -4.0, 7.0),
+                    radius: 8.0,
+                    weight: 1.0,
+                },
+                Ball {
+                    centre: Position::new(6.0, 2.0, 8.0),
+                    radius: 6.0,
+                    weight: -1.0,
+                },
+            ],
+            0.2, // threshold
+        ),
+    );
+    // a tanglecube, a cube with rounded tunnels through each face.
+    let _shapes2 = shape::Affine::new(
+        Implicit::new(
+            "x^4 - 5*x^2 + y^4 - 5*y^2 + z^4 - 5*z^2 + 11.8",
+            Bounds::new(
+                Position::new(-3.0, -3.0, -3.0),
+                Position::new(3.0, 3.0, 3.0),
+            ),
+        )?,
+        Matrix4::new_translation(&nalgebra::Vector3::new(0.0, 0.0, 12.0))
+            * Matrix4::new_scaling(4.0),
+    );
+    // a glass fractal of spheres, each with four half sized copies of the
+    // whole fractal around it.
+    let _shapes2 = Translate::new(
+        Position::new(0.0, 0.0, 22.0),
+        Fractal::new(
+            Sphere::new(10.0),
+            Sphere::new(20.0),
+            [
+                (1.0, 1.0, 1.0),
+                (1.0, -1.0, -1.0),
+                (-1.0, 1.0, -1.0),
+                (-1.0, -1.0, 1.0),
+            ]
+            .iter()
+            .map(|&(x, y, z)| {
+                let offset = nalgebra::Vector3::new(x, y, z).normalize() * 10.0;
+                Matrix4::new_translation(&offset) * Matrix4::new_scaling(0.5)
+            })
+            .collect(),
+            6, // dwell
+        ),
+    );
+    let _shapes2 = MovingTranslate::new(
+        Track::new(
+            Interpolation::Linear,
+            vec![
+                (0.0, Position::new(-5.0, 0.0, 15.0)),
+                (1.0, Position::new(5.0, 0.0, 15.0)),
+            ],
+        ),
+        Sphere::new(10.0),
+    );
+    let _shapes2 = MovingAffine::new(
+        Cuboid::new(20.0, 10.0, 14.0),
+        Track::new(
+            Interpolation::Linear,
+            vec![
+                (
+                    0.0,
+                    Matrix4::new_translation(&nalgebra::Vector3::new(0.0, 0.0, 15.0)),
+                ),
+                (
+                    1.0,
+                    Matrix4::new_translation(&nalgebra::Vector3::new(0.0, 0.0, 15.0))
+                        * Matrix4::new_rotation(nalgebra::Vector3::new(0.0, 0.0, 0.3)),
+                ),
+            ],
+        ),
+    );
+
+    let ground = Plane::new(Direction::UP, 0.0);
+    // rolling hills. A heightmap image can be used instead with
+    // Heightfield::from_image(&Image::read_ppm(path)?, 200.0, 200.0, 10.0).
+    let _ground = Heightfield::from_function(257, 257, 200.0, 200.0, |x, y| {
+        2.0 * (x / 12.0).sin() * (y / 17.0).cos() - 2.0
+    });
     let material_ground = Physical {
         refractive_index: Colour::new(3.0, 3.0, 4.0),
         refractive_absorption: None, //Some(Colour::new(0.8, 0.81, 0.8)),
@@ -114,9 +555,164 @@ fn main() -> io::Result<()> {
         }),
     ];
 
-    let scene = Scene::new(5, 7, camera, bodies);
-    let image = scene.render_ppm();
+    // a stereo pair, with zero parallax at the centre of the scene.
+    let stereo_lens = lens.clone();
+    let stereo = Stereo::new(
+        Position::new(-20.0, -60.0, 40.0),
+        Position::new(0.0, 0.0, 20.0),
+        Direction::UP,
+        6.5,  // interocular distance
+        66.0, // convergence distance
+        move |position, focal_point, shift| {
+            Perspective::new(
+                position,
+                focal_point,
+                1.2, // size, x, y
+                1.2,
+                stereo_lens.clone(),
+                Direction::UP,
+                256, // resolution
+                256,
+            )
+            .with_shift(shift)
+        },
+    );
+    let _camera = SideBySide::new(stereo.eye(Eye::Left), stereo.eye(Eye::Right));
+    let _camera = SideBySide::new(
+        OmniStereo::new(
+            Position::new(0.0, -40.0, 20.0),
+            Position::new(0.0, 0.0, 20.0),
+            Direction::UP,
+            6.5, // interocular distance
+            Eye::Left,
+            512, // resolution
+            256,
+        ),
+        OmniStereo::new(
+            Position::new(0.0, -40.0, 20.0),
+            Position::new(0.0, 0.0, 20.0),
+            Direction::UP,
+            6.5, // interocular distance
+            Eye::Right,
+            512, // resolution
+            256,
+        ),
+    );
+
+    // fit everything except the lights in the picture.
+    let _camera = Perspective::frame(
+        &Bounds::enclosing(
+            bodies
+                .iter()
+                .filter(|body| !body.is_light())
+                .filter_map(|body| body.bounds()),
+        )
+        .unwrap(),
+        &Direction::new(1.0, 3.0, -1.0),
+        1.0, // field of view
+        lens,
+        Direction::UP,
+        256, // resolution
+        256,
+    );
+
+    let filter = Filter::MitchellNetravali {
+        radius: 2.0,
+        b: 1.0 / 3.0,
+        c: 1.0 / 3.0,
+    };
+    let _filter = Filter::Box { radius: 0.5 };
+    let _filter = Filter::Tent { radius: 1.0 };
+    let _filter = Filter::Gaussian {
+        radius: 1.5,
+        alpha: 2.0,
+    };
+    let _filter = Filter::Lanczos { radius: 3.0 };
+
+    let sampler = Sampler::Sobol;
+    let _sampler = Sampler::Independent;
+    let _sampler = Sampler::Stratified;
+    let _sampler = Sampler::Halton;
+    let _sampler = Sampler::BlueNoise;
+
+    let mut scene = Scene::new(5, sampler, filter, Box::new(camera), bodies);
+
+    let integrator = SplittingTracer::new(7);
+    let _integrator = PathTracer::new(7);
+    let _integrator = Bidirectional::new(7, 5);
+    let _integrator = DirectLighting::new(20);
+    let _integrator = AmbientOcclusion::new(20, 10.0);
+    let _integrator = integrator::debug::Normals;
+    let _integrator = integrator::debug::Depth::new(100.0);
+    // photon mapping traces its photons from the lights of the scene as it
+    // is built.
+    let _integrator = PhotonMapping::new(&scene, 100_000, 100, 5.0, 7);
+
+    let denoiser = Denoiser {
+        radius: 5,
+        spatial_sigma: 3.0,
+        normal_sigma: 0.3,
+        depth_sigma: 0.05,
+        albedo_sigma: 0.1,
+        colour_sigma: 3.0,
+    };
+
+    // with no arguments the image is written to stdout. Given a path, the
+    // image is written there along with a denoised copy and every arbitrary
+    // output variable. Given a path and a range of frames, each frame is
+    // written next to the path with the frame number in its name. Given a
+    // path and "stereo", each eye of the stereo pair is written separately.
+    let args: Vec<String> = env::args().collect();
+    match args.len() {
+        1 => {
+            let image = scene.render_ppm(&integrator);
+            let mut stdout = io::stdout();
+            stdout.write_all(&image[..])
+        }
+        2 => write_render(&scene, &integrator, &denoiser, Path::new(&args[1]), 0.0),
+        3 if args[2] == "stereo" => {
+            let path = Path::new(&args[1]);
+            for (eye, name) in [(Eye::Left, "left"), (Eye::Right, "right")].iter() {
+                scene.set_camera(Box::new(stereo.eye(*eye)));
+                let eye_path = image::sibling_path(path, name, "ppm");
+                write_render(&scene, &integrator, &denoiser, &eye_path, 0.0)?;
+            }
+            Ok(())
+        }
+        _ => {
+            let path = Path::new(&args[1]);
+            let frame = |index: usize| {
+                args.get(index)
+                    .and_then(|arg| arg.parse::<u32>().ok())
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid frame"))
+            };
+            let first = frame(2)?;
+            let last = frame(3).unwrap_or(first);
+            for frame in first..=last {
+                let frame_path = image::sibling_path(path, &format!("{:04}", frame), "ppm");
+                let time = frame as f64 / FRAMES_PER_SECOND;
+                write_render(&scene, &integrator, &denoiser, &frame_path, time)?;
+            }
+            Ok(())
+        }
+    }
+}
 
-    let mut stdout = io::stdout();
-    stdout.write(&image[..]).map(|_| ())
+/// render the scene at the given time, writing the image to path along with
+/// a denoised copy and every arbitrary output variable.
+fn write_render(
+    scene: &Scene,
+    integrator: &(dyn Integrator + Sync),
+    denoiser: &Denoiser,
+    path: &Path,
+    time: f64,
+) -> io::Result<()> {
+    let render = scene.render_at(integrator, &Aov::ALL, time);
+    render.beauty.write_ppm(path)?;
+    let denoised = denoiser.denoise(&render);
+    denoised.write_ppm(&image::sibling_path(path, "denoised", "ppm"))?;
+    for (aov, image) in render.aovs {
+        image.write_pfm(&aov.path(path))?;
+    }
+    Ok(())
 }
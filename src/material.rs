@@ -3,6 +3,7 @@ pub mod physical;
 use crate::collision::Collision;
 use crate::colour::Colour;
 use crate::ray::Ray;
+use crate::shape::Direction;
 
 pub use physical::Physical;
 
@@ -21,4 +22,19 @@ pub trait Material {
     fn ray_count(&self, efficacy: f64) -> f64;
     // returns true if the object is a light source.
     fn is_light(&self) -> bool;
+    /// return the base colour of a material, the fraction of light reflected
+    /// when it arrives perpendicular to the surface.
+    fn albedo(&self) -> Colour;
+    /// return the attenuation of a ray arriving along the incoming direction
+    /// and leaving along the outgoing direction, per unit solid angle. Rays
+    /// are traced from the camera, so light travels along outgoing in reverse.
+    /// Returns None for perfectly smooth materials, where only a single
+    /// outgoing direction is possible, and for any other material whose
+    /// scattering can be sampled but not evaluated.
+    fn bsdf(
+        &self,
+        normal: &Direction,
+        incoming: &Direction,
+        outgoing: &Direction,
+    ) -> Option<Colour>;
 }
@@ -3,6 +3,7 @@ use crate::colour::Colour;
 use crate::material::Material;
 use crate::ray::Ray;
 use crate::shape::Direction;
+use std::f64::consts::PI;
 
 pub struct Physical {
     /// refractive index: real part. Corresponds to the reflective absorption.
@@ -72,6 +73,34 @@ impl Material for Physical {
     fn is_light(&self) -> bool {
         self.emission.is_some()
     }
+    fn albedo(&self) -> Colour {
+        if self.is_reflective() {
+            self.reflection_attenuation(1.0)
+        } else {
+            Colour::BLACK
+        }
+    }
+    fn bsdf(
+        &self,
+        normal: &Direction,
+        incoming: &Direction,
+        outgoing: &Direction,
+    ) -> Option<Colour> {
+        // a sharpness of 0.0 scatters rays uniformly over the hemisphere.
+        // Sharper lobes have no closed form, so are treated like perfectly
+        // smooth surfaces, which can only be sampled. Transmitted light is
+        // not accounted for.
+        if self.reflective_sharpness != Some(0.0) || !self.is_reflective() {
+            return None;
+        }
+        let cos_incidence = incoming.dot(normal);
+        let cos_outgoing = outgoing.dot(normal);
+        if cos_incidence * cos_outgoing >= 0.0 {
+            return Some(Colour::BLACK);
+        }
+        let reflectance = self.reflection_attenuation(cos_incidence.abs());
+        Some(reflectance.brighten(1.0 / (2.0 * PI * cos_outgoing.abs())))
+    }
 }
 
 impl Physical {
@@ -86,7 +115,12 @@ impl Physical {
 
             let attenuation = self.initial_refract_attenuation(cos_incidence);
             let origin = collision.collision();
-            Some(Ray::new(origin.clone(), refract_direction, attenuation))
+            Some(Ray::new(
+                origin.clone(),
+                refract_direction,
+                attenuation,
+                ray.time(),
+            ))
         } else {
             None
         }
@@ -101,7 +135,12 @@ impl Physical {
             let reflection_direction = self.reflect_direction(normal, direction);
             let attenuation = self.reflection_attenuation(cos_incidence);
             let origin = collision.collision();
-            Some(Ray::new(origin.clone(), reflection_direction, attenuation))
+            Some(Ray::new(
+                origin.clone(),
+                reflection_direction,
+                attenuation,
+                ray.time(),
+            ))
         } else {
             None
         }
@@ -110,7 +149,12 @@ impl Physical {
         let reflection_direction = self.reflect_direction(collision.normal(), ray.direction());
         let attenuation = Colour::new(1.0, 1.0, 1.0);
         let origin = collision.collision();
-        Ray::new(origin.clone(), reflection_direction, attenuation)
+        Ray::new(
+            origin.clone(),
+            reflection_direction,
+            attenuation,
+            ray.time(),
+        )
     }
 
     fn refract_direction(
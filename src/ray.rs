@@ -1,5 +1,6 @@
 use crate::colour::Colour;
 use crate::shape::{Direction, Position};
+use crate::vec3::Vec3;
 use nalgebra::base::Matrix4;
 
 #[derive(Debug, Clone)]
@@ -7,15 +8,17 @@ pub struct Ray {
     origin: Position,
     direction: Direction,
     attenuation: Colour,
+    time: f64,
 }
 
 impl Ray {
-    pub fn new(origin: Position, direction: Direction, attenuation: Colour) -> Ray {
+    pub fn new(origin: Position, direction: Direction, attenuation: Colour, time: f64) -> Ray {
         assert!(direction.normalised());
         Ray {
             origin: origin,
             direction: direction,
             attenuation: attenuation,
+            time,
         }
     }
     pub fn origin(&self) -> &Position {
@@ -27,6 +30,13 @@ impl Ray {
     pub fn attenuation(&self) -> &Colour {
         &self.attenuation
     }
+    /// the moment during the exposure at which the ray travels.
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+    pub fn geometry(&self) -> GeometricRay {
+        GeometricRay::new(self.origin.clone(), self.direction.clone(), self.time)
+    }
 
     pub fn move_along(&mut self, t: f64) {
         self.origin = self.origin.move_along(&self.direction, t);
@@ -37,6 +47,7 @@ impl Ray {
             origin: self.origin.clone(),
             direction: self.direction.clone(),
             attenuation: self.attenuation.brighten_colour(colour),
+            time: self.time,
         }
     }
     pub fn attenuate_num(&self, factor: f64) -> Ray {
@@ -44,22 +55,27 @@ impl Ray {
             origin: self.origin.clone(),
             direction: self.direction.clone(),
             attenuation: self.attenuation.brighten(1.0 / factor),
+            time: self.time,
         }
     }
 }
 
-// TODO: make shape take a gemoatric ray.
+/// A ray without any colour information, used for finding collisions with
+/// shapes. The direction is not necessarily normalised, for example after a
+/// transform, and distances along the ray are measured in multiples of the
+/// direction.
 #[derive(Debug, Clone)]
 pub struct GeometricRay {
     origin: Position,
     direction: Direction,
+    time: f64,
 }
 impl GeometricRay {
-    pub fn new(origin: Position, direction: Direction) -> GeometricRay {
-        assert!(direction.normalised());
+    pub fn new(origin: Position, direction: Direction, time: f64) -> GeometricRay {
         GeometricRay {
             origin: origin,
             direction: direction,
+            time,
         }
     }
     pub fn origin(&self) -> &Position {
@@ -68,11 +84,31 @@ impl GeometricRay {
     pub fn direction(&self) -> &Direction {
         &self.direction
     }
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// the same ray starting at a different position.
+    pub fn with_origin(&self, origin: Position) -> GeometricRay {
+        GeometricRay {
+            origin,
+            direction: self.direction.clone(),
+            time: self.time,
+        }
+    }
 
     pub fn affine_trans(&self, transform: &Matrix4<f64>) -> GeometricRay {
         GeometricRay {
             origin: self.origin.affine_trans(transform),
             direction: self.direction.affine_trans(transform),
+            time: self.time,
+        }
+    }
+    pub fn reduce_vec(&self, vector: &Vec3) -> GeometricRay {
+        GeometricRay {
+            origin: self.origin.reduce_vec(vector),
+            direction: self.direction.reduce_vec(vector),
+            time: self.time,
         }
     }
 }
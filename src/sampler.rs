@@ -0,0 +1,462 @@
+use rand::Rng;
+use std::cell::RefCell;
+use std::sync::OnceLock;
+
+/// the number of blocks of dimensions. The first block is used by the camera,
+/// then there is one block for each bounce.
+const BLOCKS: usize = 17;
+/// the number of 2d points available in each block. Points beyond this, or
+/// beyond the last block, are independent random numbers.
+const POINTS_PER_BLOCK: usize = 8;
+/// the width and height of the tileable blue noise mask.
+const TILE: usize = 64;
+/// the standard deviation of the gaussian used to find voids and clusters
+/// when building the blue noise mask.
+const SIGMA: f64 = 1.5;
+
+/// How the random numbers used to take a sample are chosen. Rather than
+/// being independent, the samples of a pixel can be spread out to cover
+/// every dimension evenly, which reduces noise for the same number of
+/// samples.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sampler {
+    /// every number is independent and uniformly random.
+    Independent,
+    /// each pair of dimensions is divided into a grid with at least as many
+    /// cells as samples, and each sample is jittered within its own cell.
+    Stratified,
+    /// the Halton sequence, with its digits scrambled differently for each
+    /// pixel.
+    Halton,
+    /// pairs of dimensions of the Sobol sequence, with Owen scrambling so that
+    /// every pixel and every pair is independent.
+    Sobol,
+    /// a Sobol sequence shared by every pixel, offset by a blue noise mask.
+    /// The remaining error is spread evenly over the image as high frequency
+    /// noise, which is less visible and easier to denoise.
+    BlueNoise,
+}
+
+/// The sample currently being taken on this thread.
+struct Current {
+    sampler: Sampler,
+    x: u32,
+    y: u32,
+    index: u32,
+    count: u32,
+    block: usize,
+    /// the number of points taken from each block.
+    used: [usize; BLOCKS],
+}
+
+thread_local! {
+    static CURRENT: RefCell<Current> = const {
+        RefCell::new(Current {
+            sampler: Sampler::Independent,
+            x: 0,
+            y: 0,
+            index: 0,
+            count: 1,
+            block: 0,
+            used: [0; BLOCKS],
+        })
+    };
+}
+
+/// start taking sample number index out of count for the pixel at x, y. Every
+/// random number taken on this thread comes from sampler until the next
+/// sample begins.
+pub fn begin(sampler: Sampler, x: u32, y: u32, index: u32, count: u32) {
+    CURRENT.with(|current| {
+        *current.borrow_mut() = Current {
+            sampler,
+            x,
+            y,
+            index,
+            count,
+            block: 0,
+            used: [0; BLOCKS],
+        }
+    });
+}
+
+/// go back to independent random numbers once a sample is finished.
+pub fn finish() {
+    begin(Sampler::Independent, 0, 0, 0, 1);
+}
+
+/// take the following random numbers from the dimensions set aside for the
+/// given bounce. This keeps the dimensions used by each bounce the same for
+/// every sample, even when earlier bounces use a varying number of them.
+pub fn set_bounce(bounce: u32) {
+    CURRENT.with(|current| current.borrow_mut().block = bounce as usize + 1);
+}
+
+/// a number between 0 and 1.
+pub fn next() -> f64 {
+    next_2d().0
+}
+
+/// a point in the unit square.
+pub fn next_2d() -> (f64, f64) {
+    let point = CURRENT.with(|current| {
+        let mut current = current.borrow_mut();
+        let block = current.block;
+        if current.sampler == Sampler::Independent
+            || block >= BLOCKS
+            || current.used[block] >= POINTS_PER_BLOCK
+        {
+            return None;
+        }
+        let pair = (block * POINTS_PER_BLOCK + current.used[block]) as u32;
+        current.used[block] += 1;
+        Some(current.sampler.point(&current, pair))
+    });
+    point.unwrap_or_else(|| {
+        let mut rng = rand::thread_rng();
+        (rng.gen(), rng.gen())
+    })
+}
+
+impl Sampler {
+    /// the point in the given pair of dimensions for the current sample.
+    fn point(self, current: &Current, pair: u32) -> (f64, f64) {
+        let seed = hash_all(&[current.x, current.y, pair]);
+        match self {
+            Sampler::Independent => {
+                let mut rng = rand::thread_rng();
+                (rng.gen(), rng.gen())
+            }
+            Sampler::Stratified => {
+                let cells = f64::sqrt(current.count as f64).ceil() as u32;
+                let cell = permute(current.index, cells * cells, seed);
+                let mut rng = rand::thread_rng();
+                (
+                    ((cell % cells) as f64 + rng.gen::<f64>()) / cells as f64,
+                    ((cell / cells) as f64 + rng.gen::<f64>()) / cells as f64,
+                )
+            }
+            Sampler::Halton => {
+                let primes = primes();
+                let pair = pair as usize;
+                (
+                    radical_inverse(primes[2 * pair], current.index, hash(seed)),
+                    radical_inverse(primes[2 * pair + 1], current.index, hash(!seed)),
+                )
+            }
+            Sampler::Sobol => {
+                let (x, y) = sobol_2d(current.index, seed);
+                (unit(x), unit(y))
+            }
+            Sampler::BlueNoise => {
+                let (x, y) = sobol_2d(current.index, hash(pair));
+                let offset = |component: u32| {
+                    let shift = hash_all(&[pair, component]) as usize;
+                    let mask_x = (current.x as usize + shift) % TILE;
+                    let mask_y = (current.y as usize + shift / TILE) % TILE;
+                    blue_noise()[mask_y * TILE + mask_x]
+                };
+                (fract(unit(x) + offset(0)), fract(unit(y) + offset(1)))
+            }
+        }
+    }
+}
+
+fn fract(x: f64) -> f64 {
+    x - x.floor()
+}
+
+fn unit(x: u32) -> f64 {
+    x as f64 / (1u64 << 32) as f64
+}
+
+// integer hash from Chris Wellons' hash prospector.
+fn hash(mut x: u32) -> u32 {
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x7feb_352d);
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x846c_a68b);
+    x ^= x >> 16;
+    x
+}
+
+fn hash_all(values: &[u32]) -> u32 {
+    values
+        .iter()
+        .fold(0x9e37_79b9, |seed, value| hash(seed ^ value))
+}
+
+/// the index'th element of a random permutation of 0..length chosen by seed,
+/// from Kensler's correlated multi-jittered sampling.
+fn permute(mut index: u32, length: u32, seed: u32) -> u32 {
+    let mut mask = length - 1;
+    mask |= mask >> 1;
+    mask |= mask >> 2;
+    mask |= mask >> 4;
+    mask |= mask >> 8;
+    mask |= mask >> 16;
+    loop {
+        index ^= seed;
+        index = index.wrapping_mul(0xe170_893d);
+        index ^= seed >> 16;
+        index ^= (index & mask) >> 4;
+        index ^= seed >> 8;
+        index = index.wrapping_mul(0x0929_eb3f);
+        index ^= seed >> 23;
+        index ^= (index & mask) >> 1;
+        index = index.wrapping_mul(1 | seed >> 27);
+        index = index.wrapping_mul(0x6935_fa69);
+        index ^= (index & mask) >> 11;
+        index = index.wrapping_mul(0x74dc_b303);
+        index ^= (index & mask) >> 2;
+        index = index.wrapping_mul(0x9e50_1cc3);
+        index ^= (index & mask) >> 2;
+        index = index.wrapping_mul(0xc860_a3df);
+        index &= mask;
+        index ^= index >> 5;
+        if index < length {
+            break;
+        }
+    }
+    index.wrapping_add(seed) % length
+}
+
+/// enough primes for a different base in every dimension.
+// is_multiple_of would need a much newer compiler than the rest of the crate.
+#[allow(clippy::manual_is_multiple_of)]
+fn primes() -> &'static [u32] {
+    static PRIMES: OnceLock<Vec<u32>> = OnceLock::new();
+    PRIMES.get_or_init(|| {
+        (2..)
+            .filter(|n: &u32| (2..*n).take_while(|d| d * d <= *n).all(|d| n % d != 0))
+            .take(2 * BLOCKS * POINTS_PER_BLOCK)
+            .collect()
+    })
+}
+
+/// mirror the digits of index in the given base about the decimal point. Each
+/// digit is replaced using a random permutation chosen by seed, which breaks
+/// up the correlation between dimensions with large bases. The permutation
+/// applies to the infinite trailing zeros too, which keeps the result
+/// uniform.
+fn radical_inverse(base: u32, mut index: u32, seed: u32) -> f64 {
+    let mut result = 0.0;
+    let mut scale = 1.0 / base as f64;
+    let mut digit = 0;
+    while scale > f64::EPSILON {
+        let permuted = permute(index % base, base, hash(seed ^ digit));
+        result += permuted as f64 * scale;
+        index /= base;
+        scale /= base as f64;
+        digit += 1;
+    }
+    result
+}
+
+/// the first two dimensions of the Sobol sequence, shuffled and Owen
+/// scrambled by seed. Following Burley's practical hash-based Owen
+/// scrambling, higher dimensions are made by scrambling further pairs with
+/// different seeds.
+fn sobol_2d(index: u32, seed: u32) -> (u32, u32) {
+    let index = owen_scramble(index, seed);
+
+    // the first dimension is the van der Corput sequence. The direction
+    // numbers of the second are each the previous one xored with itself
+    // shifted right.
+    let x = index.reverse_bits();
+    let mut y = 0;
+    let mut direction = 1 << 31;
+    let mut bits = index;
+    while bits != 0 {
+        if bits & 1 == 1 {
+            y ^= direction;
+        }
+        bits >>= 1;
+        direction ^= direction >> 1;
+    }
+
+    (
+        owen_scramble(x, hash(seed ^ 1)),
+        owen_scramble(y, hash(seed ^ 2)),
+    )
+}
+
+/// randomly flip each bit depending on the bits above it, so that the
+/// stratification of the sequence is kept.
+fn owen_scramble(x: u32, seed: u32) -> u32 {
+    // the Laine-Karras permutation scrambles each bit based on the bits
+    // below it, so the bits are reversed either side.
+    let mut x = x.reverse_bits();
+    x = x.wrapping_add(seed);
+    x ^= x.wrapping_mul(0x6c50_b47c);
+    x ^= x.wrapping_mul(0xb82f_1e52);
+    x ^= x.wrapping_mul(0xc7af_e638);
+    x ^= x.wrapping_mul(0x8d22_f6e6);
+    x.reverse_bits()
+}
+
+/// a tileable mask where nearby values are as different as possible, built
+/// once using Ulichney's void and cluster method.
+fn blue_noise() -> &'static [f64] {
+    static MASK: OnceLock<Vec<f64>> = OnceLock::new();
+    MASK.get_or_init(void_and_cluster)
+}
+
+/// the energy of each point is the sum of gaussians centred on each chosen
+/// point. Clusters are chosen points with the most energy, and voids are
+/// unchosen points with the least.
+#[derive(Clone)]
+struct Pattern {
+    kernel: Vec<f64>,
+    chosen: Vec<bool>,
+    energy: Vec<f64>,
+}
+
+impl Pattern {
+    fn new() -> Pattern {
+        let wrap = |d: usize| d.min(TILE - d) as f64;
+        let kernel = (0..TILE * TILE)
+            .map(|i| {
+                let (dx, dy) = (wrap(i % TILE), wrap(i / TILE));
+                f64::exp(-(dx * dx + dy * dy) / (2.0 * SIGMA * SIGMA))
+            })
+            .collect();
+        Pattern {
+            kernel,
+            chosen: vec![false; TILE * TILE],
+            energy: vec![0.0; TILE * TILE],
+        }
+    }
+
+    fn toggle(&mut self, point: usize) {
+        self.chosen[point] = !self.chosen[point];
+        let sign = if self.chosen[point] { 1.0 } else { -1.0 };
+        let (px, py) = (point % TILE, point / TILE);
+        for y in 0..TILE {
+            let row = ((y + TILE - py) % TILE) * TILE;
+            for x in 0..TILE {
+                self.energy[y * TILE + x] += sign * self.kernel[row + (x + TILE - px) % TILE];
+            }
+        }
+    }
+
+    fn extreme(&self, chosen: bool, sign: f64) -> usize {
+        (0..TILE * TILE)
+            .filter(|&point| self.chosen[point] == chosen)
+            .max_by(|&a, &b| {
+                (sign * self.energy[a])
+                    .partial_cmp(&(sign * self.energy[b]))
+                    .unwrap()
+            })
+            .unwrap()
+    }
+
+    fn tightest_cluster(&self) -> usize {
+        self.extreme(true, 1.0)
+    }
+
+    fn largest_void(&self) -> usize {
+        self.extreme(false, -1.0)
+    }
+}
+
+fn void_and_cluster() -> Vec<f64> {
+    let size = TILE * TILE;
+
+    // start from a fixed sparse random pattern, then move points from the
+    // tightest cluster to the largest void until they are evenly spread. It
+    // settles after a few hundred swaps, but rounding in the energies could
+    // leave two points trading places forever, so give up after one swap per
+    // pixel.
+    let mut initial = Pattern::new();
+    for i in 0..size as u32 / 10 {
+        let point = hash(i) as usize % size;
+        if !initial.chosen[point] {
+            initial.toggle(point);
+        }
+    }
+    for _swap in 0..size {
+        let cluster = initial.tightest_cluster();
+        initial.toggle(cluster);
+        let void = initial.largest_void();
+        initial.toggle(void);
+        if void == cluster {
+            break;
+        }
+    }
+
+    // rank the initial points by removing clusters, then the rest by filling
+    // voids.
+    let mut ranks = vec![0; size];
+    let chosen = initial.chosen.iter().filter(|&&chosen| chosen).count();
+    let mut pattern = initial.clone();
+    for rank in (0..chosen).rev() {
+        let cluster = pattern.tightest_cluster();
+        pattern.toggle(cluster);
+        ranks[cluster] = rank;
+    }
+    for rank in chosen..size {
+        let void = initial.largest_void();
+        initial.toggle(void);
+        ranks[void] = rank;
+    }
+
+    ranks
+        .into_iter()
+        .map(|rank| (rank as f64 + 0.5) / size as f64)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Sampler; 5] = [
+        Sampler::Independent,
+        Sampler::Stratified,
+        Sampler::Halton,
+        Sampler::Sobol,
+        Sampler::BlueNoise,
+    ];
+
+    /// the first point of the given bounce for each of count samples of a
+    /// pixel.
+    fn points(sampler: Sampler, x: u32, y: u32, bounce: u32, count: u32) -> Vec<(f64, f64)> {
+        let points = (0..count)
+            .map(|index| {
+                begin(sampler, x, y, index, count);
+                set_bounce(bounce);
+                next_2d()
+            })
+            .collect();
+        finish();
+        points
+    }
+
+    #[test]
+    fn points_are_in_unit_square() {
+        for sampler in ALL.iter() {
+            for bounce in [0, 5, BLOCKS as u32].iter() {
+                for (u, v) in points(*sampler, 3, 7, *bounce, 64) {
+                    assert!((0.0..1.0).contains(&u), "{:?} gave {}", sampler, u);
+                    assert!((0.0..1.0).contains(&v), "{:?} gave {}", sampler, v);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn points_are_stratified() {
+        for sampler in [Sampler::Stratified, Sampler::Sobol].iter() {
+            for (x, y) in [(0, 0), (12, 5)].iter() {
+                // 16 samples put exactly one point in each cell of a 4 by 4
+                // grid.
+                let mut cells = [0; 16];
+                for (u, v) in points(*sampler, *x, *y, 2, 16) {
+                    cells[(v * 4.0) as usize * 4 + (u * 4.0) as usize] += 1;
+                }
+                assert_eq!(cells, [1; 16], "{:?}", sampler);
+            }
+        }
+    }
+}
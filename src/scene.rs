@@ -1,101 +1,358 @@
+use crate::aov::Aov;
 use crate::body::Body;
 use crate::camera::Camera;
+use crate::collision::Collision;
 use crate::colour::Colour;
+use crate::filter::Filter;
+use crate::image::Image;
+use crate::integrator::Integrator;
 use crate::ray::Ray;
+use crate::sampler::{self, Sampler};
 
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
 use std::cmp::Ordering::Equal;
+use std::iter;
+
+// pixels with less total weight than this are left black.
+const MIN_WEIGHT: f64 = 1e-10;
+
+/// The images produced by rendering a scene.
+pub struct Render {
+    pub beauty: Image,
+    /// an estimate of the variance of each pixel in the beauty pass.
+    pub variance: Image,
+    pub aovs: Vec<(Aov, Image)>,
+}
+
+impl Render {
+    pub fn aov(&self, aov: Aov) -> Option<&Image> {
+        self.aovs
+            .iter()
+            .find(|(pass, _)| *pass == aov)
+            .map(|(_, image)| image)
+    }
+}
+
+/// The colour of each pass seen through a point on the image.
+struct Sample {
+    x: f64,
+    y: f64,
+    /// the beauty pass followed by each arbitrary output variable.
+    passes: Vec<Colour>,
+}
+
+/// Accumulates samples into an image, weighted by a reconstruction filter.
+/// Passes which aren't colours are averaged over the samples inside each
+/// pixel instead, as negative filter weights would ring and blend ids.
+struct Film {
+    width: u32,
+    height: u32,
+    /// whether each pass goes through the reconstruction filter.
+    filtered: Vec<bool>,
+    /// the weighted sum of the samples for each pass.
+    sums: Vec<Image>,
+    /// the weighted sum of the squares of the beauty pass.
+    squares: Image,
+    /// the sum of the weights and the sum of the squares of the weights for
+    /// each pixel.
+    weights: Vec<(f64, f64)>,
+    /// the number of samples inside each pixel.
+    counts: Vec<u32>,
+}
+
+impl Film {
+    /// filtered says whether each pass goes through the reconstruction
+    /// filter.
+    fn new(width: u32, height: u32, filtered: Vec<bool>) -> Film {
+        Film {
+            width,
+            height,
+            sums: filtered.iter().map(|_| Image::new(width, height)).collect(),
+            filtered,
+            squares: Image::new(width, height),
+            weights: vec![(0.0, 0.0); (width * height) as usize],
+            counts: vec![0; (width * height) as usize],
+        }
+    }
+
+    /// add a sample to every pixel the filter reaches.
+    fn splat(&mut self, filter: &Filter, sample: &Sample) {
+        let radius = filter.radius();
+        // the centre of pixel x is at x + 0.5.
+        let x_min = (sample.x - 0.5 - radius).ceil().max(0.0) as u32;
+        let y_min = (sample.y - 0.5 - radius).ceil().max(0.0) as u32;
+        let x_max = (sample.x - 0.5 + radius)
+            .floor()
+            .min(self.width as f64 - 1.0);
+        let y_max = (sample.y - 0.5 + radius)
+            .floor()
+            .min(self.height as f64 - 1.0);
+        if x_max < 0.0 || y_max < 0.0 {
+            return;
+        }
+
+        let passes = self.sums.iter_mut().zip(&sample.passes);
+        let (filtered, unfiltered): (Vec<_>, Vec<_>) = passes
+            .zip(&self.filtered)
+            .partition(|(_, filtered)| **filtered);
+
+        let (x, y) = (sample.x.floor(), sample.y.floor());
+        if x >= 0.0 && y >= 0.0 && x < self.width as f64 && y < self.height as f64 {
+            let (x, y) = (x as u32, y as u32);
+            for ((image, colour), _) in unfiltered {
+                let total = image.get(x, y) + colour;
+                image.set(x, y, total);
+            }
+            self.counts[(y * self.width + x) as usize] += 1;
+        }
+
+        let mut filtered: Vec<_> = filtered.into_iter().map(|(pass, _)| pass).collect();
+        let beauty = &sample.passes[0];
+        let square = beauty.brighten_colour(beauty);
+        for y in y_min..=y_max as u32 {
+            for x in x_min..=x_max as u32 {
+                let weight = filter.weight(x as f64 + 0.5 - sample.x, y as f64 + 0.5 - sample.y);
+                for (image, colour) in filtered.iter_mut() {
+                    let total = image.get(x, y) + &colour.brighten(weight);
+                    image.set(x, y, total);
+                }
+                let total = self.squares.get(x, y) + &square.brighten(weight);
+                self.squares.set(x, y, total);
+
+                let (total, total_sq) = &mut self.weights[(y * self.width + x) as usize];
+                *total += weight;
+                *total_sq += weight * weight;
+            }
+        }
+    }
+
+    /// the sum of two films of the same size, as if every sample splatted
+    /// into either had been splatted into one.
+    fn merge(mut self, other: Film) -> Film {
+        let add = |image: &mut Image, other: &Image| {
+            for y in 0..image.height() {
+                for x in 0..image.width() {
+                    let total = image.get(x, y) + other.get(x, y);
+                    image.set(x, y, total);
+                }
+            }
+        };
+        for (image, other) in self.sums.iter_mut().zip(&other.sums) {
+            add(image, other);
+        }
+        add(&mut self.squares, &other.squares);
+        for (weights, other) in self.weights.iter_mut().zip(&other.weights) {
+            weights.0 += other.0;
+            weights.1 += other.1;
+        }
+        for (count, other) in self.counts.iter_mut().zip(&other.counts) {
+            *count += other;
+        }
+        self
+    }
+
+    /// the beauty pass, its variance and every other pass.
+    fn develop(mut self) -> (Image, Image, Vec<Image>) {
+        let mut variance = Image::new(self.width, self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let pixel = (y * self.width + x) as usize;
+                let (total, total_sq) = self.weights[pixel];
+                let count = self.counts[pixel];
+                for (image, filtered) in self.sums.iter_mut().zip(&self.filtered) {
+                    if *filtered && total.abs() >= MIN_WEIGHT {
+                        let mean = image.get(x, y).brighten(1.0 / total);
+                        image.set(x, y, mean);
+                    } else if !*filtered && count > 0 {
+                        let mean = image.get(x, y).brighten(1.0 / count as f64);
+                        image.set(x, y, mean);
+                    }
+                }
+                if total.abs() < MIN_WEIGHT {
+                    continue;
+                }
+
+                // the variance of the weighted mean of the samples.
+                let mean = self.sums[0].get(x, y);
+                let spread =
+                    &self.squares.get(x, y).brighten(1.0 / total) - &mean.brighten_colour(mean);
+                let effective_samples = total * total / total_sq;
+                let pixel_variance = Colour::new(
+                    spread.red().max(0.0),
+                    spread.green().max(0.0),
+                    spread.blue().max(0.0),
+                )
+                .brighten(1.0 / effective_samples);
+                variance.set(x, y, pixel_variance);
+            }
+        }
+        let beauty = self.sums.remove(0);
+        (beauty, variance, self.sums)
+    }
+}
 
 pub struct Scene<'a> {
     samples: u32,
-    bounces: u32,
-    camera: Camera,
+    sampler: Sampler,
+    filter: Filter,
+    camera: Box<dyn Camera + Sync + 'a>,
     bodies: Vec<Box<dyn Body + Sync + 'a>>,
 }
 
-// if the attenuation is low, then the resulting pixel will be largely affected
-// by the colour, for high attenuation, barely any effect. We should therefore
-// cast more rays if the light is more effective to spend more computational
-// power where it is required. this value is chosen arbitrarily to approximate
-// even levels of detail.
-const EFFICACY_CONSTANT: f64 = 40.0;
-
 impl<'a> Scene<'a> {
     pub fn new(
         samples: u32,
-        bounces: u32,
-        camera: Camera,
+        sampler: Sampler,
+        filter: Filter,
+        camera: Box<dyn Camera + Sync + 'a>,
         bodies: Vec<Box<dyn Body + Sync + 'a>>,
     ) -> Scene<'a> {
         Scene {
-            samples: samples,
-            bounces: bounces,
-            camera: camera,
-            bodies: bodies,
+            samples,
+            sampler,
+            filter,
+            camera,
+            bodies,
         }
     }
 
-    pub fn sampler(&self, ray: &Ray, bounce: u32) -> Colour {
-        if bounce >= self.bounces {
-            return Colour::BLACK;
-        }
+    pub fn set_camera(&mut self, camera: Box<dyn Camera + Sync + 'a>) {
+        self.camera = camera;
+    }
+
+    pub fn bodies(&self) -> &[Box<dyn Body + Sync + 'a>] {
+        &self.bodies
+    }
+
+    /// a random moment while the shutter of the camera is open.
+    pub fn sample_time(&self) -> f64 {
+        let (open, close) = self.camera.shutter();
+        open + (close - open) * sampler::next()
+    }
+
+    /// Return the closest body a ray hits, along with the collision.
+    pub fn collision(&self, ray: &Ray) -> Option<(&(dyn Body + Sync + 'a), Collision)> {
+        self.collision_index(ray)
+            .map(|(index, collision)| (self.bodies[index].as_ref(), collision))
+    }
 
+    /// Return the index of the closest body a ray hits, along with the
+    /// collision.
+    pub fn collision_index(&self, ray: &Ray) -> Option<(usize, Collision)> {
         self.bodies
             .iter()
-            .filter_map(|body| {
-                body.collision(ray.origin(), ray.direction())
-                    .map(|collision| (body, collision))
+            .enumerate()
+            .filter_map(|(index, body)| {
+                body.collision(&ray.geometry())
+                    .map(|collision| (index, collision))
             })
-            .min_by(|(_b1, collision1), (_b2, collision2)| {
+            .min_by(|(_i1, collision1), (_i2, collision2)| {
                 collision1.t().partial_cmp(&collision2.t()).unwrap_or(Equal)
             })
-            .map(|(body, collision)| {
-                let att = ray.attenuation();
-                // efficacy is how much a given ray is expected to affect the pixel.
-                let efficacy = 1.0 + EFFICACY_CONSTANT * (att.red() + att.blue() + att.green());
-                let rays = body.rays(ray, &collision, efficacy);
-
-                let mut colour = body.emission().brighten_colour(&att);
-                for ray in rays {
-                    colour = &colour + &self.sampler(&ray, bounce + 1);
-                }
-                colour
-            })
-            .unwrap_or(Colour::BLACK)
     }
 
-    pub fn render_ppm(&self) -> Vec<u8> {
+    /// render the beauty pass using an integrator, as well as any arbitrary
+    /// output variables requested. Every pass shares the same camera rays.
+    pub fn render(&self, integrator: &(dyn Integrator + Sync), aovs: &[Aov]) -> Render {
+        self.render_at(integrator, aovs, 0.0)
+    }
+
+    /// render a frame of an animation. The shutter of the camera opens and
+    /// closes relative to time.
+    pub fn render_at(
+        &self,
+        integrator: &(dyn Integrator + Sync),
+        aovs: &[Aov],
+        time: f64,
+    ) -> Render {
         let (x_res, y_res) = self.camera.resolution();
 
-        let mut header = format!("P6 {} {} 255 ", x_res, y_res).as_bytes().to_vec();
-        let mut pixels = (0..y_res)
-            .into_par_iter()
-            .map(|y| {
-                (0..x_res)
-                    .map(|x| {
-                        let mut colour = Colour::new(0.0, 0.0, 0.0);
-
-                        (0..self.samples).for_each(|_| {
-                            let (start, direction) = self.camera.generate_ray(x as f64, y as f64);
-                            colour = &colour
-                                + &self.sampler(
-                                    &Ray::new(start, direction, Colour::new(1.0, 1.0, 1.0)),
-                                    0,
-                                );
-                        });
-
-                        colour = colour.brighten(1.0 / (self.samples as f64));
-
-                        colour.to_bytes()
-                    })
-                    .flatten()
-                    .collect::<Vec<u8>>()
-            })
-            .flatten()
+        // each thread splats the rows it renders into its own film, and the
+        // films are added together at the end, so samples are never stored.
+        // the beauty pass is always filtered.
+        let filtered: Vec<bool> = iter::once(true)
+            .chain(aovs.iter().map(Aov::is_colour))
             .collect();
+        let film = (0..y_res)
+            .into_par_iter()
+            .fold(
+                || Film::new(x_res, y_res, filtered.clone()),
+                |mut film, y| {
+                    for x in 0..x_res {
+                        for index in 0..self.samples {
+                            let sample = self.sample(integrator, aovs, x, y, index, time);
+                            film.splat(&self.filter, &sample);
+                        }
+                    }
+                    film
+                },
+            )
+            .reduce(|| Film::new(x_res, y_res, filtered.clone()), Film::merge);
+        let (beauty, variance, images) = film.develop();
+        Render {
+            beauty,
+            variance,
+            aovs: aovs.iter().cloned().zip(images).collect(),
+        }
+    }
 
-        header.append(&mut pixels);
-        header
+    /// trace sample number index through a point in the pixel at x, y.
+    fn sample(
+        &self,
+        integrator: &(dyn Integrator + Sync),
+        aovs: &[Aov],
+        x: u32,
+        y: u32,
+        index: u32,
+        frame_time: f64,
+    ) -> Sample {
+        sampler::begin(self.sampler, x, y, index, self.samples);
+        let (jitter_x, jitter_y) = sampler::next_2d();
+        let x = x as f64 + jitter_x;
+        let y = y as f64 + jitter_y;
+        let time = frame_time + self.sample_time();
+        let passes = match self.camera.generate_ray(x, y, time) {
+            Some((start, direction)) => {
+                let ray = Ray::new(start, direction, Colour::new(1.0, 1.0, 1.0), time);
+                let mut passes = vec![integrator.radiance(self, &ray)];
+                passes.extend(aovs.iter().map(|aov| aov.evaluate(self, &ray)));
+                passes
+            }
+            None => vec![Colour::BLACK; aovs.len() + 1],
+        };
+        sampler::finish();
+        Sample { x, y, passes }
+    }
+
+    pub fn render_ppm(&self, integrator: &(dyn Integrator + Sync)) -> Vec<u8> {
+        self.render(integrator, &[]).beauty.to_ppm()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_passes_are_not_filtered() {
+        let mut film = Film::new(2, 1, vec![true, false]);
+        let filter = Filter::Tent { radius: 2.0 };
+        for (x, id) in [(0.5, 1.0), (1.5, 2.0)].iter() {
+            let colour = Colour::new(*id, *id, *id);
+            film.splat(
+                &filter,
+                &Sample {
+                    x: *x,
+                    y: 0.5,
+                    passes: vec![colour.clone(), colour],
+                },
+            );
+        }
+        let (beauty, _, passes) = film.develop();
+        // the beauty pass blends the neighbouring sample in.
+        assert!(beauty.get(0, 0).red() > 1.0);
+        assert_eq!(passes[0].get(0, 0).red(), 1.0);
+        assert_eq!(passes[0].get(1, 0).red(), 2.0);
     }
 }
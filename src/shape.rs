@@ -1,55 +1,137 @@
 mod direction_position;
 
 mod affine;
+mod bounds;
+mod capsule;
+mod cone;
+mod convex;
 mod cuboid;
+mod cylinder;
 mod difference;
+mod disc;
 mod fractal;
+mod heightfield;
+pub mod implicit;
 mod intersection;
 pub mod march;
+mod metaballs;
+mod moving_affine;
+mod moving_translate;
+pub mod patch;
 mod plane;
+mod polygon;
+mod polynomial;
+mod quad;
+mod quadric;
 mod scale;
 mod sphere;
+mod torus;
 mod translate;
 mod union;
 
 pub use affine::Affine;
+pub use bounds::Bounds;
+pub use capsule::Capsule;
+pub use cone::Cone;
 pub use cuboid::Cuboid;
+pub use cylinder::Cylinder;
 pub use difference::Difference;
+pub use disc::Disc;
 pub use fractal::Fractal;
+pub use heightfield::Heightfield;
+pub use implicit::Implicit;
 pub use intersection::Intersection;
+pub use metaballs::{Ball, Metaballs};
+pub use moving_affine::MovingAffine;
+pub use moving_translate::MovingTranslate;
+pub use patch::Patch;
 pub use plane::Plane;
+pub use polygon::Polygon;
+pub use quad::Quad;
+pub use quadric::Quadric;
 pub use scale::Scale;
 pub use sphere::Sphere;
+pub use torus::Torus;
 pub use translate::Translate;
 pub use union::Union;
 
 use crate::collision::Collision;
+use crate::ray::GeometricRay;
 pub use direction_position::{Direction, Position};
 
+/// A point chosen at random on the surface of a shape. The pdf is with respect
+/// to surface area.
+#[derive(Debug, Clone)]
+pub struct SurfaceSample {
+    pub position: Position,
+    pub normal: Direction,
+    pub pdf: f64,
+}
+
 /// A basic Shape trait. A Shape should be able to, given a line representing an
 /// origin and a direction, deterine a collision with that line and the shape.
 /// In addition to this, the Shape must be able to return a surface normal.
 pub trait Shape {
     /// collision to the outside of a shape. normal points to ouside the shape.
-    fn collision(&self, origin: &Position, direction: &Direction) -> Option<Collision>;
+    fn collision(&self, ray: &GeometricRay) -> Option<Collision>;
     /// collision to the inside of a shape. normal points to outside shape.
-    fn collision_in(&self, origin: &Position, direction: &Direction) -> Option<Collision>;
+    fn collision_in(&self, ray: &GeometricRay) -> Option<Collision>;
+    /// pick a random point on the surface of a shape. Shapes which cannot be
+    /// sampled, such as infinite shapes, return None.
+    fn sample_surface(&self) -> Option<SurfaceSample> {
+        None
+    }
+    /// an axis aligned box containing the shape. Shapes which are infinite,
+    /// or whose extent is unknown, return None.
+    fn bounds(&self) -> Option<Bounds> {
+        None
+    }
 }
 
 impl<'a, T: Shape> Shape for &'a T {
-    fn collision(&self, origin: &Position, direction: &Direction) -> Option<Collision> {
-        (*self).collision(origin, direction)
+    fn collision(&self, ray: &GeometricRay) -> Option<Collision> {
+        (*self).collision(ray)
+    }
+    fn collision_in(&self, ray: &GeometricRay) -> Option<Collision> {
+        (*self).collision_in(ray)
+    }
+    fn sample_surface(&self) -> Option<SurfaceSample> {
+        (*self).sample_surface()
     }
-    fn collision_in(&self, origin: &Position, direction: &Direction) -> Option<Collision> {
-        (*self).collision_in(origin, direction)
+    fn bounds(&self) -> Option<Bounds> {
+        (*self).bounds()
     }
 }
 
 impl<T: Shape> Shape for Box<T> {
-    fn collision(&self, origin: &Position, direction: &Direction) -> Option<Collision> {
-        self.as_ref().collision(origin, direction)
+    fn collision(&self, ray: &GeometricRay) -> Option<Collision> {
+        self.as_ref().collision(ray)
     }
-    fn collision_in(&self, origin: &Position, direction: &Direction) -> Option<Collision> {
-        self.as_ref().collision_in(origin, direction)
+    fn collision_in(&self, ray: &GeometricRay) -> Option<Collision> {
+        self.as_ref().collision_in(ray)
+    }
+    fn sample_surface(&self) -> Option<SurfaceSample> {
+        self.as_ref().sample_surface()
+    }
+    fn bounds(&self) -> Option<Bounds> {
+        self.as_ref().bounds()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// check there is a hit at t with the given normal.
+    pub fn assert_hit(hit: Option<Collision>, t: f64, normal: &Direction) {
+        let hit = hit.expect("expected a hit");
+        assert!((hit.t() - t).abs() < 1e-9, "hit at {} not {}", hit.t(), t);
+        let alignment = hit.normal().dot(&normal.normalise());
+        assert!(
+            (alignment - 1.0).abs() < 1e-9,
+            "normal {:?} not {:?}",
+            hit.normal(),
+            normal
+        );
     }
 }
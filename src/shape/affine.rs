@@ -1,4 +1,5 @@
-use crate::shape::{Collision, Direction, Position, Shape};
+use crate::ray::GeometricRay;
+use crate::shape::{Bounds, Collision, Shape, SurfaceSample};
 use nalgebra::base::Matrix4;
 
 pub struct Affine<S> {
@@ -19,18 +20,40 @@ impl<S: Shape> Affine<S> {
 }
 
 impl<S: Shape> Shape for Affine<S> {
-    fn collision(&self, origin: &Position, direction: &Direction) -> Option<(Collision)> {
-        let new_origin = origin.affine_trans(&self.inv_transform);
-        let new_direction = direction.affine_trans(&self.inv_transform);
+    fn collision(&self, ray: &GeometricRay) -> Option<(Collision)> {
+        let new_ray = ray.affine_trans(&self.inv_transform);
         self.shape
-            .collision(&new_origin, &new_direction)
+            .collision(&new_ray)
             .map(|collision| collision.affine_trans(&self.transform))
     }
-    fn collision_in(&self, origin: &Position, direction: &Direction) -> Option<(Collision)> {
-        let new_origin = origin.affine_trans(&self.inv_transform);
-        let new_direction = direction.affine_trans(&self.inv_transform);
+    fn collision_in(&self, ray: &GeometricRay) -> Option<(Collision)> {
+        let new_ray = ray.affine_trans(&self.inv_transform);
         self.shape
-            .collision_in(&new_origin, &new_direction)
+            .collision_in(&new_ray)
             .map(|collision| collision.affine_trans(&self.transform))
     }
+
+    fn sample_surface(&self) -> Option<SurfaceSample> {
+        self.shape.sample_surface().map(|sample| {
+            // the area of a surface element changes depending on its
+            // orientation relative to the transform.
+            let normal = sample.normal.affine_normal_inv(&self.inv_transform);
+            let determinant = self.transform.determinant();
+            SurfaceSample {
+                position: sample.position.affine_trans(&self.transform),
+                normal: normal.normalise(),
+                pdf: sample.pdf / (determinant * normal.len()).abs(),
+            }
+        })
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        let bounds = self.shape.bounds()?;
+        Bounds::from_points(
+            bounds
+                .corners()
+                .iter()
+                .map(|corner| corner.affine_trans(&self.transform)),
+        )
+    }
 }
@@ -0,0 +1,99 @@
+use crate::shape::Position;
+
+/// An axis aligned box which completely contains a shape.
+#[derive(Debug, Clone)]
+pub struct Bounds {
+    pub min: Position,
+    pub max: Position,
+}
+
+impl Bounds {
+    pub fn new(min: Position, max: Position) -> Bounds {
+        Bounds { min, max }
+    }
+
+    /// the smallest bounds containing every point. Returns None if there are
+    /// no points.
+    pub fn from_points<I: IntoIterator<Item = Position>>(points: I) -> Option<Bounds> {
+        points.into_iter().fold(None, |bounds, point| {
+            Some(match bounds {
+                None => Bounds::new(point.clone(), point),
+                Some(bounds) => Bounds::new(
+                    Position::new(
+                        bounds.min.x().min(point.x()),
+                        bounds.min.y().min(point.y()),
+                        bounds.min.z().min(point.z()),
+                    ),
+                    Position::new(
+                        bounds.max.x().max(point.x()),
+                        bounds.max.y().max(point.y()),
+                        bounds.max.z().max(point.z()),
+                    ),
+                ),
+            })
+        })
+    }
+
+    /// the smallest bounds containing all of the bounds. Returns None if
+    /// there are none.
+    pub fn enclosing<I: IntoIterator<Item = Bounds>>(bounds: I) -> Option<Bounds> {
+        bounds.into_iter().fold(None, |total, bounds| match total {
+            None => Some(bounds),
+            Some(total) => Some(total.union(&bounds)),
+        })
+    }
+
+    pub fn corners(&self) -> Vec<Position> {
+        let (min, max) = (&self.min, &self.max);
+        vec![
+            Position::new(min.x(), min.y(), min.z()),
+            Position::new(min.x(), min.y(), max.z()),
+            Position::new(min.x(), max.y(), min.z()),
+            Position::new(min.x(), max.y(), max.z()),
+            Position::new(max.x(), min.y(), min.z()),
+            Position::new(max.x(), min.y(), max.z()),
+            Position::new(max.x(), max.y(), min.z()),
+            Position::new(max.x(), max.y(), max.z()),
+        ]
+    }
+
+    /// the smallest bounds containing both bounds.
+    pub fn union(&self, other: &Bounds) -> Bounds {
+        Bounds::from_points(vec![
+            self.min.clone(),
+            self.max.clone(),
+            other.min.clone(),
+            other.max.clone(),
+        ])
+        .unwrap()
+    }
+
+    /// the region inside both bounds. Returns None if they do not overlap.
+    pub fn intersection(&self, other: &Bounds) -> Option<Bounds> {
+        let min = Position::new(
+            self.min.x().max(other.min.x()),
+            self.min.y().max(other.min.y()),
+            self.min.z().max(other.min.z()),
+        );
+        let max = Position::new(
+            self.max.x().min(other.max.x()),
+            self.max.y().min(other.max.y()),
+            self.max.z().min(other.max.z()),
+        );
+        if min.x() > max.x() || min.y() > max.y() || min.z() > max.z() {
+            None
+        } else {
+            Some(Bounds::new(min, max))
+        }
+    }
+
+    pub fn centre(&self) -> Position {
+        (&self.min + &self.max).scale(0.5)
+    }
+
+    /// the radius of the smallest sphere around the centre containing the
+    /// bounds.
+    pub fn radius(&self) -> f64 {
+        self.min.dist(&self.max) / 2.0
+    }
+}
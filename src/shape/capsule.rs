@@ -0,0 +1,137 @@
+use crate::ray::GeometricRay;
+use crate::sampler;
+use crate::shape::convex::Span;
+use crate::shape::cylinder::side_span;
+use crate::shape::{Bounds, Collision, Direction, Position, Shape, SurfaceSample};
+use std::f64::consts::PI;
+
+/// A cylinder with hemispherical ends, centred on the origin with its axis
+/// along z.
+#[derive(Debug)]
+pub struct Capsule {
+    radius: f64,
+    half_height: f64,
+}
+
+impl Capsule {
+    /// height is the length of the cylindrical part, not including the
+    /// hemispheres on either end.
+    pub fn new(radius: f64, height: f64) -> Capsule {
+        Capsule {
+            radius,
+            half_height: height / 2.0,
+        }
+    }
+
+    /// the part of a ray inside the sphere at one end of the capsule.
+    fn end_span(&self, ray: &GeometricRay, centre_z: f64) -> Option<Span> {
+        let centre = Position::new(0.0, 0.0, centre_z);
+        let (direction, offset) = (ray.direction(), ray.origin() - &centre);
+        let a = direction.dot(direction);
+        let b = 2.0 * direction.dot_position(&offset);
+        let c = offset.dot(&offset) - self.radius * self.radius;
+
+        Span::quadratic(a, b, c, |t| {
+            (&ray.origin().move_along(direction, t) - &centre)
+                .to_direction()
+                .normalise()
+        })
+        .pop()
+    }
+
+    fn span(&self, ray: &GeometricRay) -> Option<Span> {
+        let middle = side_span(ray, self.radius)
+            .and_then(|side| side.intersect(Span::slab(ray, -self.half_height, self.half_height)?));
+        // the capsule is convex, so the pieces a ray passes through overlap.
+        vec![
+            middle,
+            self.end_span(ray, self.half_height),
+            self.end_span(ray, -self.half_height),
+        ]
+        .into_iter()
+        .flatten()
+        .fold(None, |total: Option<Span>, span| match total {
+            Some(total) => Some(total.union(span)),
+            None => Some(span),
+        })
+    }
+}
+
+impl Shape for Capsule {
+    fn collision(&self, ray: &GeometricRay) -> Option<Collision> {
+        self.span(ray)?.collision(ray)
+    }
+
+    fn collision_in(&self, ray: &GeometricRay) -> Option<Collision> {
+        self.span(ray)?.collision_in(ray)
+    }
+
+    fn sample_surface(&self) -> Option<SurfaceSample> {
+        let (u, v) = sampler::next_2d();
+        let choice = sampler::next();
+
+        // choose the side or the ends with probability proportional to area.
+        let side_area = 4.0 * PI * self.radius * self.half_height;
+        let ends_area = 4.0 * PI * self.radius * self.radius;
+        let (position, normal) = if choice * (side_area + ends_area) < side_area {
+            let angle = 2.0 * PI * u;
+            (
+                Position::new(
+                    self.radius * angle.cos(),
+                    self.radius * angle.sin(),
+                    (2.0 * v - 1.0) * self.half_height,
+                ),
+                Direction::new(angle.cos(), angle.sin(), 0.0),
+            )
+        } else {
+            // a point on a sphere, moved to the end on the same side.
+            let normal = Direction::random();
+            let centre_z = self.half_height * normal.z().signum();
+            (
+                normal
+                    .to_position()
+                    .scale(self.radius)
+                    .move_along(&Direction::UP, centre_z),
+                normal,
+            )
+        };
+
+        Some(SurfaceSample {
+            position,
+            normal,
+            pdf: 1.0 / (side_area + ends_area),
+        })
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        let half_length = self.half_height + self.radius;
+        Some(Bounds::new(
+            Position::new(-self.radius, -self.radius, -half_length),
+            Position::new(self.radius, self.radius, half_length),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::tests::assert_hit;
+
+    #[test]
+    fn enters_and_leaves() {
+        let capsule = Capsule::new(1.0, 2.0);
+        let side = GeometricRay::new(Position::new(0.0, -5.0, 0.0), Direction::FORWARDS, 0.0);
+        assert_hit(capsule.collision(&side), 4.0, &Direction::BACKWARDS);
+        let end = GeometricRay::new(Position::new(0.0, 0.0, 5.0), Direction::DOWN, 0.0);
+        assert_hit(capsule.collision(&end), 3.0, &Direction::UP);
+        // beside the axis the ray meets the curve of the end.
+        let end = GeometricRay::new(Position::new(0.6, 0.0, 5.0), Direction::DOWN, 0.0);
+        assert_hit(capsule.collision(&end), 3.2, &Direction::new(0.6, 0.0, 0.8));
+
+        let inside = GeometricRay::new(Position::ORIGIN, Direction::FORWARDS, 0.0);
+        assert!(capsule.collision(&inside).is_none());
+        assert_hit(capsule.collision_in(&inside), 1.0, &Direction::FORWARDS);
+        let inside = GeometricRay::new(Position::ORIGIN, Direction::DOWN, 0.0);
+        assert_hit(capsule.collision_in(&inside), 2.0, &Direction::DOWN);
+    }
+}
@@ -0,0 +1,139 @@
+use crate::ray::GeometricRay;
+use crate::sampler;
+use crate::shape::convex::Span;
+use crate::shape::{Bounds, Collision, Direction, Position, Shape, SurfaceSample};
+use std::f64::consts::PI;
+
+/// A solid cone centred on the origin, with a flat circular base below and
+/// its point above, along the z axis.
+#[derive(Debug)]
+pub struct Cone {
+    radius: f64,
+    half_height: f64,
+    /// the radius of the cone divided by its height.
+    slope: f64,
+}
+
+impl Cone {
+    /// radius is the radius of the base.
+    pub fn new(radius: f64, height: f64) -> Cone {
+        Cone {
+            radius,
+            half_height: height / 2.0,
+            slope: radius / height,
+        }
+    }
+
+    /// the outwards normal of the side of the cone, at a point on the side.
+    fn side_normal(&self, point: &Position) -> Direction {
+        let below_point = self.half_height - point.z();
+        Direction::new(point.x(), point.y(), self.slope * self.slope * below_point).normalise()
+    }
+
+    fn span(&self, ray: &GeometricRay) -> Option<Span> {
+        let (origin, direction) = (ray.origin(), ray.direction());
+        // inside the double cone x^2 + y^2 <= (slope * (half_height - z))^2.
+        // Between the base and the point only the lower half is left.
+        let k2 = self.slope * self.slope;
+        let below_point = self.half_height - origin.z();
+        let a = direction.x() * direction.x() + direction.y() * direction.y()
+            - k2 * direction.z() * direction.z();
+        let b = 2.0 * (origin.x() * direction.x() + origin.y() * direction.y())
+            + 2.0 * k2 * below_point * direction.z();
+        let c = origin.x() * origin.x() + origin.y() * origin.y() - k2 * below_point * below_point;
+
+        let sides = Span::quadratic(a, b, c, |t| {
+            self.side_normal(&origin.move_along(direction, t))
+        });
+        sides.into_iter().find_map(|side| {
+            let ends = Span::slab(ray, -self.half_height, self.half_height)?;
+            // the upper half of the double cone only touches the slab at
+            // the point.
+            side.intersect(ends)
+                .filter(|span| span.enter.t < span.exit.t)
+        })
+    }
+}
+
+impl Shape for Cone {
+    fn collision(&self, ray: &GeometricRay) -> Option<Collision> {
+        self.span(ray)?.collision(ray)
+    }
+
+    fn collision_in(&self, ray: &GeometricRay) -> Option<Collision> {
+        self.span(ray)?.collision_in(ray)
+    }
+
+    fn sample_surface(&self) -> Option<SurfaceSample> {
+        let (u, v) = sampler::next_2d();
+        let choice = sampler::next();
+
+        // choose the side or the base with probability proportional to area.
+        let slant =
+            f64::sqrt(self.radius * self.radius + 4.0 * self.half_height * self.half_height);
+        let side_area = PI * self.radius * slant;
+        let base_area = PI * self.radius * self.radius;
+        let angle = 2.0 * PI * v;
+        // the area within a distance of the point or the centre of the base
+        // grows with the square of the distance.
+        let distance = u.sqrt();
+        let (position, normal) = if choice * (side_area + base_area) < side_area {
+            let position = Position::new(
+                self.radius * distance * angle.cos(),
+                self.radius * distance * angle.sin(),
+                self.half_height * (1.0 - 2.0 * distance),
+            );
+            let normal = self.side_normal(&position);
+            (position, normal)
+        } else {
+            (
+                Position::new(
+                    self.radius * distance * angle.cos(),
+                    self.radius * distance * angle.sin(),
+                    -self.half_height,
+                ),
+                Direction::DOWN,
+            )
+        };
+
+        Some(SurfaceSample {
+            position,
+            normal,
+            pdf: 1.0 / (side_area + base_area),
+        })
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Some(Bounds::new(
+            Position::new(-self.radius, -self.radius, -self.half_height),
+            Position::new(self.radius, self.radius, self.half_height),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::tests::assert_hit;
+
+    #[test]
+    fn enters_and_leaves() {
+        // halfway up, the side is 0.5 from the axis and slopes in by 0.5 for
+        // every 1 it rises.
+        let cone = Cone::new(1.0, 2.0);
+        let side = GeometricRay::new(Position::new(0.0, -5.0, 0.0), Direction::FORWARDS, 0.0);
+        assert_hit(cone.collision(&side), 4.5, &Direction::new(0.0, -1.0, 0.5));
+        let base = GeometricRay::new(Position::new(0.0, 0.0, -5.0), Direction::UP, 0.0);
+        assert_hit(cone.collision(&base), 4.0, &Direction::DOWN);
+
+        let inside = GeometricRay::new(Position::ORIGIN, Direction::FORWARDS, 0.0);
+        assert!(cone.collision(&inside).is_none());
+        assert_hit(
+            cone.collision_in(&inside),
+            0.5,
+            &Direction::new(0.0, 1.0, 0.5),
+        );
+        let inside = GeometricRay::new(Position::ORIGIN, Direction::DOWN, 0.0);
+        assert_hit(cone.collision_in(&inside), 1.0, &Direction::DOWN);
+    }
+}
@@ -0,0 +1,166 @@
+use crate::ray::GeometricRay;
+use crate::shape::polynomial;
+use crate::shape::{Collision, Direction};
+
+/// A point where a ray crosses the surface of a shape. The normal points out
+/// of the shape.
+pub struct Intersect {
+    pub t: f64,
+    pub normal: Direction,
+}
+
+impl Intersect {
+    pub fn min_max(self, other: Intersect) -> (Intersect, Intersect) {
+        if self.t < other.t {
+            (self, other)
+        } else {
+            (other, self)
+        }
+    }
+
+    pub fn max(self, other: Intersect) -> Intersect {
+        if self.t > other.t {
+            self
+        } else {
+            other
+        }
+    }
+
+    pub fn min(self, other: Intersect) -> Intersect {
+        if self.t < other.t {
+            self
+        } else {
+            other
+        }
+    }
+
+    /// a point infinitely far along the ray, in either direction.
+    fn infinite(t: f64) -> Intersect {
+        Intersect {
+            t,
+            normal: Direction::UP,
+        }
+    }
+}
+
+/// The part of a line which lies inside a convex shape, from where it enters
+/// to where it leaves. Either end may be infinite.
+pub struct Span {
+    pub enter: Intersect,
+    pub exit: Intersect,
+}
+
+impl Span {
+    pub fn everywhere() -> Span {
+        Span {
+            enter: Intersect::infinite(f64::NEG_INFINITY),
+            exit: Intersect::infinite(f64::INFINITY),
+        }
+    }
+
+    /// the part of a ray between the planes z = min and z = max.
+    pub fn slab(ray: &GeometricRay, min: f64, max: f64) -> Option<Span> {
+        let (origin, direction) = (ray.origin(), ray.direction());
+        if direction.z() == 0.0 {
+            return if origin.z() >= min && origin.z() <= max {
+                Some(Span::everywhere())
+            } else {
+                None
+            };
+        }
+        let bottom = Intersect {
+            t: (min - origin.z()) / direction.z(),
+            normal: Direction::DOWN,
+        };
+        let top = Intersect {
+            t: (max - origin.z()) / direction.z(),
+            normal: Direction::UP,
+        };
+        let (enter, exit) = bottom.min_max(top);
+        Some(Span { enter, exit })
+    }
+
+    /// the parts of a line where a t^2 + b t + c <= 0. normal gives the
+    /// outwards normal at a given t.
+    pub fn quadratic<F>(a: f64, b: f64, c: f64, normal: F) -> Vec<Span>
+    where
+        F: Fn(f64) -> Direction,
+    {
+        let at = |t: f64| Intersect {
+            t,
+            normal: normal(t),
+        };
+        match polynomial::quadratic(a, b, c)[..] {
+            // the sign never changes.
+            [] if c <= 0.0 => vec![Span::everywhere()],
+            [] => Vec::new(),
+            [root] if b > 0.0 => vec![Span {
+                enter: Intersect::infinite(f64::NEG_INFINITY),
+                exit: at(root),
+            }],
+            [root] => vec![Span {
+                enter: at(root),
+                exit: Intersect::infinite(f64::INFINITY),
+            }],
+            [root1, root2] if a > 0.0 => vec![Span {
+                enter: at(root1),
+                exit: at(root2),
+            }],
+            [root1, root2] => vec![
+                Span {
+                    enter: Intersect::infinite(f64::NEG_INFINITY),
+                    exit: at(root1),
+                },
+                Span {
+                    enter: at(root2),
+                    exit: Intersect::infinite(f64::INFINITY),
+                },
+            ],
+            _ => unreachable!(),
+        }
+    }
+
+    /// the part of the line inside both spans.
+    pub fn intersect(self, other: Span) -> Option<Span> {
+        let span = Span {
+            enter: self.enter.max(other.enter),
+            exit: self.exit.min(other.exit),
+        };
+        if span.enter.t <= span.exit.t {
+            Some(span)
+        } else {
+            None
+        }
+    }
+
+    /// the part of the line inside either span. The spans must overlap, which
+    /// is always true for pieces of the same convex shape.
+    pub fn union(self, other: Span) -> Span {
+        Span {
+            enter: self.enter.min(other.enter),
+            exit: self.exit.max(other.exit),
+        }
+    }
+
+    /// where the ray enters the shape, if it starts outside.
+    pub fn collision(self, ray: &GeometricRay) -> Option<Collision> {
+        let Span { enter, exit } = self;
+        if enter.t > 0.0 && enter.t < exit.t && enter.t.is_finite() {
+            let position = ray.origin().move_along(ray.direction(), enter.t);
+            Some(Collision::new(enter.t, enter.normal, position))
+        } else {
+            None
+        }
+    }
+
+    /// where the ray leaves the shape.
+    pub fn collision_in(self, ray: &GeometricRay) -> Option<Collision> {
+        let Span { enter, exit } = self;
+        if exit.t > 0.0 && enter.t < exit.t && exit.t.is_finite() {
+            let position = ray.origin().move_along(ray.direction(), exit.t);
+            Some(Collision::new(exit.t, exit.normal, position))
+        } else {
+            None
+        }
+    }
+}
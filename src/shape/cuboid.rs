@@ -1,4 +1,7 @@
-use crate::shape::{Collision, Direction, Position, Shape};
+use crate::ray::GeometricRay;
+use crate::sampler;
+use crate::shape::convex::Intersect;
+use crate::shape::{Bounds, Collision, Direction, Position, Shape, SurfaceSample};
 
 #[derive(Debug)]
 pub struct Cuboid {
@@ -7,37 +10,6 @@ pub struct Cuboid {
     half_z: f64,
 }
 
-struct Intersect {
-    t: f64,
-    normal: Direction,
-}
-
-impl Intersect {
-    fn min_max(self, other: Intersect) -> (Intersect, Intersect) {
-        if self.t < other.t {
-            (self, other)
-        } else {
-            (other, self)
-        }
-    }
-
-    fn max(self, other: Intersect) -> Intersect {
-        if self.t > other.t {
-            self
-        } else {
-            other
-        }
-    }
-
-    fn min(self, other: Intersect) -> Intersect {
-        if self.t < other.t {
-            self
-        } else {
-            other
-        }
-    }
-}
-
 impl Cuboid {
     pub fn new(x_length: f64, y_length: f64, z_length: f64) -> Cuboid {
         Cuboid {
@@ -90,7 +62,8 @@ impl Cuboid {
 }
 
 impl Shape for Cuboid {
-    fn collision(&self, origin: &Position, direction: &Direction) -> Option<(Collision)> {
+    fn collision(&self, ray: &GeometricRay) -> Option<(Collision)> {
+        let (origin, direction) = (ray.origin(), ray.direction());
         let (collision_in, collision_out) = self.possible_collisions(origin, direction);
 
         if collision_in.t > 0.0 && collision_in.t < collision_out.t {
@@ -105,7 +78,8 @@ impl Shape for Cuboid {
         }
     }
 
-    fn collision_in(&self, origin: &Position, direction: &Direction) -> Option<(Collision)> {
+    fn collision_in(&self, ray: &GeometricRay) -> Option<(Collision)> {
+        let (origin, direction) = (ray.origin(), ray.direction());
         let (collision_in, collision_out) = self.possible_collisions(origin, direction);
 
         if collision_out.t > 0.0 && collision_in.t < collision_out.t {
@@ -119,4 +93,49 @@ impl Shape for Cuboid {
             None
         }
     }
+
+    fn sample_surface(&self) -> Option<SurfaceSample> {
+        let (u, v) = sampler::next_2d();
+        let (u, v) = (u * 2.0 - 1.0, v * 2.0 - 1.0);
+        let (face, choice) = sampler::next_2d();
+        let side = if face < 0.5 { 1.0 } else { -1.0 };
+
+        // choose a face with probability proportional to its area.
+        let x_area = self.half_y * self.half_z;
+        let y_area = self.half_x * self.half_z;
+        let z_area = self.half_x * self.half_y;
+        let choice = choice * (x_area + y_area + z_area);
+
+        let (position, normal) = if choice < x_area {
+            (
+                Position::new(side * self.half_x, u * self.half_y, v * self.half_z),
+                Direction::new(side, 0.0, 0.0),
+            )
+        } else if choice < x_area + y_area {
+            (
+                Position::new(u * self.half_x, side * self.half_y, v * self.half_z),
+                Direction::new(0.0, side, 0.0),
+            )
+        } else {
+            (
+                Position::new(u * self.half_x, v * self.half_y, side * self.half_z),
+                Direction::new(0.0, 0.0, side),
+            )
+        };
+
+        // each face has an area of 4 times its half lengths multiplied.
+        let area = 8.0 * (x_area + y_area + z_area);
+        Some(SurfaceSample {
+            position,
+            normal,
+            pdf: 1.0 / area,
+        })
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Some(Bounds::new(
+            Position::new(-self.half_x, -self.half_y, -self.half_z),
+            Position::new(self.half_x, self.half_y, self.half_z),
+        ))
+    }
 }
@@ -0,0 +1,117 @@
+use crate::ray::GeometricRay;
+use crate::sampler;
+use crate::shape::convex::Span;
+use crate::shape::{Bounds, Collision, Direction, Position, Shape, SurfaceSample};
+use std::f64::consts::PI;
+
+/// A solid cylinder with flat ends, centred on the origin with its axis along
+/// z.
+#[derive(Debug)]
+pub struct Cylinder {
+    radius: f64,
+    half_height: f64,
+}
+
+impl Cylinder {
+    pub fn new(radius: f64, height: f64) -> Cylinder {
+        Cylinder {
+            radius,
+            half_height: height / 2.0,
+        }
+    }
+
+    fn span(&self, ray: &GeometricRay) -> Option<Span> {
+        let side = side_span(ray, self.radius)?;
+        let ends = Span::slab(ray, -self.half_height, self.half_height)?;
+        side.intersect(ends)
+    }
+}
+
+/// the part of a ray inside an infinitely long cylinder with its axis along z.
+pub fn side_span(ray: &GeometricRay, radius: f64) -> Option<Span> {
+    let (origin, direction) = (ray.origin(), ray.direction());
+    let a = direction.x() * direction.x() + direction.y() * direction.y();
+    let b = 2.0 * (origin.x() * direction.x() + origin.y() * direction.y());
+    let c = origin.x() * origin.x() + origin.y() * origin.y() - radius * radius;
+
+    Span::quadratic(a, b, c, |t| {
+        let point = origin.move_along(direction, t);
+        Direction::new(point.x(), point.y(), 0.0).normalise()
+    })
+    .pop()
+}
+
+impl Shape for Cylinder {
+    fn collision(&self, ray: &GeometricRay) -> Option<Collision> {
+        self.span(ray)?.collision(ray)
+    }
+
+    fn collision_in(&self, ray: &GeometricRay) -> Option<Collision> {
+        self.span(ray)?.collision_in(ray)
+    }
+
+    fn sample_surface(&self) -> Option<SurfaceSample> {
+        let (u, v) = sampler::next_2d();
+        let (end, choice) = sampler::next_2d();
+
+        // choose the side or an end with probability proportional to area.
+        let side_area = 4.0 * PI * self.radius * self.half_height;
+        let end_area = PI * self.radius * self.radius;
+        let angle = 2.0 * PI * u;
+        let (position, normal) = if choice * (side_area + 2.0 * end_area) < side_area {
+            (
+                Position::new(
+                    self.radius * angle.cos(),
+                    self.radius * angle.sin(),
+                    (2.0 * v - 1.0) * self.half_height,
+                ),
+                Direction::new(angle.cos(), angle.sin(), 0.0),
+            )
+        } else {
+            let side = if end < 0.5 { 1.0 } else { -1.0 };
+            let distance = self.radius * v.sqrt();
+            (
+                Position::new(
+                    distance * angle.cos(),
+                    distance * angle.sin(),
+                    side * self.half_height,
+                ),
+                Direction::new(0.0, 0.0, side),
+            )
+        };
+
+        Some(SurfaceSample {
+            position,
+            normal,
+            pdf: 1.0 / (side_area + 2.0 * end_area),
+        })
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Some(Bounds::new(
+            Position::new(-self.radius, -self.radius, -self.half_height),
+            Position::new(self.radius, self.radius, self.half_height),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::tests::assert_hit;
+
+    #[test]
+    fn enters_and_leaves() {
+        let cylinder = Cylinder::new(1.0, 2.0);
+        let side = GeometricRay::new(Position::new(0.0, -5.0, 0.0), Direction::FORWARDS, 0.0);
+        assert_hit(cylinder.collision(&side), 4.0, &Direction::BACKWARDS);
+        let end = GeometricRay::new(Position::new(0.0, 0.0, 5.0), Direction::DOWN, 0.0);
+        assert_hit(cylinder.collision(&end), 4.0, &Direction::UP);
+
+        let inside = GeometricRay::new(Position::ORIGIN, Direction::FORWARDS, 0.0);
+        assert!(cylinder.collision(&inside).is_none());
+        assert_hit(cylinder.collision_in(&inside), 1.0, &Direction::FORWARDS);
+        let inside = GeometricRay::new(Position::ORIGIN, Direction::DOWN, 0.0);
+        assert_hit(cylinder.collision_in(&inside), 1.0, &Direction::DOWN);
+    }
+}
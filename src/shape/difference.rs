@@ -1,4 +1,5 @@
-use crate::shape::{Collision, Direction, Position, Shape};
+use crate::ray::GeometricRay;
+use crate::shape::{Bounds, Collision, Shape};
 
 pub struct Difference<B, N> {
     base: B,
@@ -15,11 +16,11 @@ impl<B, N> Difference<B, N> {
 }
 
 impl<B: Shape, N: Shape> Shape for Difference<B, N> {
-    fn collision(&self, origin: &Position, direction: &Direction) -> Option<(Collision)> {
+    fn collision(&self, ray: &GeometricRay) -> Option<(Collision)> {
         // base collision does not exist
-        let base_col = self.base.collision(origin, direction)?;
+        let base_col = self.base.collision(ray)?;
         // base collision exists but negative collision doesn't
-        match self.negative.collision(origin, direction) {
+        match self.negative.collision(ray) {
             None => Some(base_col),
             Some(neg_col) => {
                 // base collision is in front of negative collision
@@ -27,15 +28,16 @@ impl<B: Shape, N: Shape> Shape for Difference<B, N> {
                     return Some(base_col);
                 }
                 // we never exit the negative object, so no collision.
-                let neg_col_out = self.negative.collision_in(origin, direction)?;
+                let neg_col_out = self.negative.collision_in(ray)?;
                 // we exit the negative object before we intersect the base object
                 if neg_col_out.t() < base_col.t() {
                     // recursive call in case the shape is convex.
-                    let new_origin = origin.move_along(direction, neg_col_out.t());
-                    return self.collision(&new_origin, direction);
+                    let new_ray =
+                        ray.with_origin(ray.origin().move_along(ray.direction(), neg_col_out.t()));
+                    return self.collision(&new_ray);
                 }
                 // we never exit the base object, so the collision is the negative out.
-                self.base.collision_in(origin, direction).map_or(
+                self.base.collision_in(ray).map_or(
                     Some(neg_col_out.flip_normal()),
                     |base_col_out| {
                         // we exit the negative object before we exit the base
@@ -44,8 +46,10 @@ impl<B: Shape, N: Shape> Shape for Difference<B, N> {
                         // we exit the negative object after we exit the base.
                         } else {
                             // call recursively in case base or negative is convex.
-                            let new_origin = origin.move_along(direction, neg_col_out.t());
-                            return self.collision(&new_origin, direction);
+                            let new_ray = ray.with_origin(
+                                ray.origin().move_along(ray.direction(), neg_col_out.t()),
+                            );
+                            return self.collision(&new_ray);
                         }
                     },
                 )
@@ -53,12 +57,13 @@ impl<B: Shape, N: Shape> Shape for Difference<B, N> {
         }
     }
 
-    fn collision_in(&self, origin: &Position, direction: &Direction) -> Option<(Collision)> {
+    fn collision_in(&self, ray: &GeometricRay) -> Option<(Collision)> {
         // no base collision means that the ray is outside the shape. TODO: fix
         // edge case where ray begins inside base and negative, therefore
         // outside shape
-        self.base.collision_in(origin, direction).map(|base_col| {
-            match self.negative.collision(origin, direction) {
+        self.base
+            .collision_in(ray)
+            .map(|base_col| match self.negative.collision(ray) {
                 Some(collision) => {
                     if collision.t() < base_col.t() {
                         collision.flip_normal()
@@ -67,7 +72,10 @@ impl<B: Shape, N: Shape> Shape for Difference<B, N> {
                     }
                 }
                 _ => base_col,
-            }
-        })
+            })
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        self.base.bounds()
     }
 }
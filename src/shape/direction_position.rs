@@ -1,6 +1,7 @@
+use crate::sampler;
 use crate::vec3::Vec3;
 use nalgebra::base::{Matrix4, RowVector4, Vector4};
-use rand::Rng;
+use std::f64::consts::PI;
 use std::ops::{Add, Sub};
 
 /// a direction vector. Some methods are disabled, for example, it doesn't make
@@ -110,25 +111,15 @@ impl Direction {
     /// creates a random unit vector. The distribution of random points on the
     /// unit sphere whould be unbiased.
     pub fn random() -> Direction {
-        let mut rng = rand::thread_rng();
-        loop {
-            // Wolfram math world sphere point picking (Marsaglia 1972)
-            let a = rng.gen::<f64>() * 2.0 - 1.0;
-            let b = rng.gen::<f64>() * 2.0 - 1.0;
-            let squares = a * a + b * b;
-
-            if squares >= 1.0 {
-                continue;
-            }
-
-            // a^2 + b^2 + c^2 = 1
-            let c = f64::sqrt(1.0 - squares);
-            let x = 2.0 * a * c;
-            let y = 2.0 * b * c;
-            let z = 1.0 - 2.0 * squares;
-
-            break Direction(Vec3::new(x, y, z));
-        }
+        // Archimedes' hat-box theorem: a uniform height and angle on the
+        // enclosing cylinder projects to a uniform point on the sphere. Unlike
+        // rejection sampling, this uses exactly two dimensions of the sampler.
+        let (u, v) = sampler::next_2d();
+        let z = 1.0 - 2.0 * u;
+        let radius = f64::sqrt(1.0 - z * z);
+        let angle = 2.0 * PI * v;
+
+        Direction(Vec3::new(radius * angle.cos(), radius * angle.sin(), z))
     }
 
     /// caluculate a perfect reflection between an incident ray (self) and a
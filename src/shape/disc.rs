@@ -0,0 +1,116 @@
+use crate::ray::GeometricRay;
+use crate::sampler;
+use crate::shape::{Bounds, Collision, Direction, Position, Shape, SurfaceSample};
+use std::f64::consts::PI;
+
+/// A flat circular disc with no thickness, centred on the origin and facing
+/// up along z. It can be hit from either side, with the normal facing the
+/// ray, and emits from both sides when used as a light. Useful for lids and
+/// area lights.
+///
+/// Like Polygon it is open geometry rather than a solid: it has no inside,
+/// so collision_in never hits. It shouldn't be used in a Difference or
+/// Intersection, or as a refractive body. Use a short Cylinder for a solid
+/// disc.
+#[derive(Debug)]
+pub struct Disc {
+    radius: f64,
+}
+
+impl Disc {
+    pub fn new(radius: f64) -> Disc {
+        Disc { radius }
+    }
+}
+
+impl Shape for Disc {
+    fn collision(&self, ray: &GeometricRay) -> Option<Collision> {
+        let (origin, direction) = (ray.origin(), ray.direction());
+        if direction.z() == 0.0 {
+            return None;
+        }
+        let t = -origin.z() / direction.z();
+        if t <= 0.0 {
+            return None;
+        }
+        let collision = origin.move_along(direction, t);
+        if collision.x() * collision.x() + collision.y() * collision.y() > self.radius * self.radius
+        {
+            return None;
+        }
+
+        let normal = if origin.z() > 0.0 {
+            Direction::UP
+        } else {
+            Direction::DOWN
+        };
+        Some(Collision::new(t, normal, collision))
+    }
+
+    fn collision_in(&self, _ray: &GeometricRay) -> Option<Collision> {
+        None
+    }
+
+    /// either side is chosen with equal chance, as collision gives the normal
+    /// of whichever side is hit.
+    fn sample_surface(&self) -> Option<SurfaceSample> {
+        let (u, v) = sampler::next_2d();
+        let distance = self.radius * u.sqrt();
+        let angle = 2.0 * PI * v;
+        let normal = if sampler::next() < 0.5 {
+            Direction::UP
+        } else {
+            Direction::DOWN
+        };
+        Some(SurfaceSample {
+            position: Position::new(distance * angle.cos(), distance * angle.sin(), 0.0),
+            normal,
+            // the area of both sides.
+            pdf: 1.0 / (2.0 * PI * self.radius * self.radius),
+        })
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Some(Bounds::new(
+            Position::new(-self.radius, -self.radius, 0.0),
+            Position::new(self.radius, self.radius, 0.0),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::tests::assert_hit;
+
+    #[test]
+    fn hits_either_side() {
+        let disc = Disc::new(1.0);
+        let above = GeometricRay::new(Position::new(0.5, 0.0, 5.0), Direction::DOWN, 0.0);
+        assert_hit(disc.collision(&above), 5.0, &Direction::UP);
+        let below = GeometricRay::new(Position::new(0.5, 0.0, -5.0), Direction::UP, 0.0);
+        assert_hit(disc.collision(&below), 5.0, &Direction::DOWN);
+        assert!(disc.collision_in(&below).is_none());
+
+        let outside = GeometricRay::new(Position::new(1.5, 0.0, 5.0), Direction::DOWN, 0.0);
+        assert!(disc.collision(&outside).is_none());
+    }
+
+    #[test]
+    fn samples_either_side() {
+        let disc = Disc::new(1.0);
+        let (mut up, mut down) = (0, 0);
+        for _ in 0..100 {
+            let sample = disc.sample_surface().unwrap();
+            let position = &sample.position;
+            assert!(position.x() * position.x() + position.y() * position.y() <= 1.0 + 1e-9);
+            assert!((sample.pdf - 1.0 / (2.0 * PI)).abs() < 1e-9);
+            if sample.normal.z() > 0.0 {
+                up += 1;
+            } else {
+                down += 1;
+            }
+        }
+        assert!(up > 0 && down > 0);
+    }
+}
@@ -1,8 +1,14 @@
-use crate::shape::{Collision, Direction, Position, Shape};
+use crate::ray::GeometricRay;
+use crate::shape::{Bounds, Collision, Shape};
 use nalgebra::base::Matrix4;
 use std::cmp::Ordering;
+use std::cmp::Ordering::Equal;
 use std::collections::BinaryHeap;
 
+/// A shape made of a primitive and smaller copies of the whole fractal,
+/// placed by each of the transforms. The bounds must contain the primitive
+/// and every transformed copy of the bounds. Copies nested dwell deep are
+/// not expanded further, and are treated as solid copies of the bounds.
 pub struct Fractal<P, B> {
     primitive: P,
     bounds: B,
@@ -10,17 +16,18 @@ pub struct Fractal<P, B> {
     dwell: u32,
 }
 
-/// Collisions are in global space, not local space.
+/// Collisions are in global space, not local space. Bounds carry how deeply
+/// they are nested.
 enum Event {
     Real(Collision),
-    Bound(Collision, Matrix4<f64>),
+    Bound(Collision, Matrix4<f64>, u32),
 }
 
 impl Event {
     fn get_collision(&self) -> &Collision {
         match self {
             Event::Real(collision) => &collision,
-            Event::Bound(collision, _) => &collision,
+            Event::Bound(collision, _, _) => &collision,
         }
     }
 }
@@ -42,10 +49,17 @@ impl PartialOrd for Event {
     }
 }
 
-fn push_opt<T: Ord>(heap: &mut BinaryHeap<T>, item: Option<T>) {
-    match item {
-        None => {}
-        Some(element) => heap.push(element),
+// how far past the point where the ray leaves one copy of the primitive to
+// look for an overlapping copy it is still inside.
+const OVERLAP_STEP: f64 = 1e-6;
+
+/// where the ray leaves the shape, if it starts inside it: the first surface
+/// it crosses is on the way out.
+fn exit_from<S: Shape>(shape: &S, ray: &GeometricRay) -> Option<Collision> {
+    let exit = shape.collision_in(ray)?;
+    match shape.collision(ray) {
+        Some(entry) if entry.t() < exit.t() => None,
+        _ => Some(exit),
     }
 }
 
@@ -67,9 +81,9 @@ impl<P: Shape, B: Shape> Fractal<P, B> {
 
     fn get_inner_events(
         &self,
-        origin: &Position,
-        direction: &Direction,
+        ray: &GeometricRay,
         transform: &Matrix4<f64>,
+        depth: u32,
     ) -> Vec<Event> {
         assert!(transform.is_invertible());
         let mut result: Vec<Event> = self
@@ -79,40 +93,42 @@ impl<P: Shape, B: Shape> Fractal<P, B> {
                 let global_transform = transform * inner_transform;
                 let inv_transform = global_transform.try_inverse().unwrap();
 
-                let new_origin = origin.affine_trans(&inv_transform);
-                let new_direction = direction.affine_trans(&inv_transform);
+                let new_ray = ray.affine_trans(&inv_transform);
 
-                self.bounds
-                    .collision(&new_origin, &new_direction)
-                    .map(|collision| {
-                        Event::Bound(collision.affine_trans(&global_transform), global_transform)
-                    })
+                self.bounds.collision(&new_ray).map(|collision| {
+                    Event::Bound(
+                        collision.affine_trans(&global_transform),
+                        global_transform,
+                        depth + 1,
+                    )
+                })
             })
             .collect();
 
         let inv_transform = transform.try_inverse().unwrap();
-        let new_origin = origin.affine_trans(&inv_transform);
-        let new_direction = direction.affine_trans(&inv_transform);
+        let new_ray = ray.affine_trans(&inv_transform);
 
-        if let Some(collision) = self.primitive.collision(&new_origin, &new_direction) {
+        if let Some(collision) = self.primitive.collision(&new_ray) {
             let collision = collision.affine_trans(transform);
             result.push(Event::Real(collision));
         }
         result
     }
 
-    fn collision_dwell(&self, origin: &Position, direction: &Direction) -> Option<(Collision)> {
-        let collision = self.bounds.collision(origin, direction)?;
+    fn collision_dwell(&self, ray: &GeometricRay) -> Option<(Collision)> {
+        let collision = self.bounds.collision(ray)?;
 
         let mut heap = BinaryHeap::new();
-        heap.push(Event::Bound(collision, Matrix4::identity()));
-
-        for _i in 0..self.dwell {
-            match heap.pop() {
-                None => return None,
-                Some(Event::Real(collision)) => return Some(collision),
-                Some(Event::Bound(_, transform)) => {
-                    self.get_inner_events(origin, direction, &transform)
+        heap.push(Event::Bound(collision, Matrix4::identity(), 0));
+
+        // bounds dwell deep are solid, the same as in furthest_exit, so a ray
+        // entering one can find its way out again.
+        loop {
+            match heap.pop()? {
+                Event::Real(collision) => return Some(collision),
+                Event::Bound(collision, _, depth) if depth >= self.dwell => return Some(collision),
+                Event::Bound(_, transform, depth) => {
+                    self.get_inner_events(ray, &transform, depth)
                         .into_iter()
                         .for_each(|event| {
                             heap.push(event);
@@ -120,26 +136,173 @@ impl<P: Shape, B: Shape> Fractal<P, B> {
                 }
             }
         }
-        heap.pop().map(|event| match event {
-            Event::Real(collision) => collision,
-            Event::Bound(collision, _) => collision,
+    }
+
+    /// the furthest point the ray leaves any copy of the primitive it starts
+    /// inside. Copies of the bounds nested dwell deep are treated as solid.
+    fn furthest_exit(&self, ray: &GeometricRay, dwell: u32) -> Option<Collision> {
+        let mut exits = Vec::new();
+        let mut stack = match exit_from(&self.bounds, ray) {
+            None => return None,
+            Some(exit) if dwell == 0 => return Some(exit),
+            Some(_) => vec![(Matrix4::identity(), 0)],
+        };
+
+        // only the copies of the bounds the ray starts inside need expanding.
+        while let Some((transform, depth)) = stack.pop() {
+            let inv_transform: Matrix4<f64> = transform.try_inverse().unwrap();
+            let new_ray = ray.affine_trans(&inv_transform);
+            if let Some(exit) = exit_from(&self.primitive, &new_ray) {
+                exits.push(exit.affine_trans(&transform));
+            }
+
+            for inner_transform in &self.transforms {
+                let global_transform = transform * inner_transform;
+                let inv_transform = global_transform.try_inverse().unwrap();
+                let new_ray = ray.affine_trans(&inv_transform);
+                if let Some(exit) = exit_from(&self.bounds, &new_ray) {
+                    if depth + 1 < dwell {
+                        stack.push((global_transform, depth + 1));
+                    } else {
+                        exits.push(exit.affine_trans(&global_transform));
+                    }
+                }
+            }
+        }
+
+        exits.into_iter().max_by(|collision1, collision2| {
+            collision1.t().partial_cmp(&collision2.t()).unwrap_or(Equal)
         })
     }
-    pub fn collision_in_dwell(
-        &self,
-        _origin: &Position,
-        _direction: &Direction,
-        _dwell: u32,
-    ) -> Option<(Collision)> {
-        unimplemented!()
+
+    pub fn collision_in_dwell(&self, ray: &GeometricRay, dwell: u32) -> Option<(Collision)> {
+        let mut exit = self.furthest_exit(ray, dwell)?;
+
+        // the point where the ray leaves one copy may be inside another which
+        // overlaps it, so carry on from there until the ray is outside them
+        // all.
+        let direction = ray.direction();
+        let step = OVERLAP_STEP / direction.len();
+        for _i in 0..dwell {
+            let start = exit.t() + step;
+            let onward = GeometricRay::new(
+                ray.origin().move_along(direction, start),
+                direction.clone(),
+                ray.time(),
+            );
+            match self.furthest_exit(&onward, dwell) {
+                None => break,
+                Some(next) => {
+                    exit = Collision::new(
+                        start + next.t(),
+                        next.normal().clone(),
+                        next.collision().clone(),
+                    )
+                }
+            }
+        }
+        Some(exit)
     }
 }
 
 impl<P: Shape, B: Shape> Shape for Fractal<P, B> {
-    fn collision(&self, origin: &Position, direction: &Direction) -> Option<(Collision)> {
-        self.collision_dwell(origin, direction)
+    fn collision(&self, ray: &GeometricRay) -> Option<(Collision)> {
+        self.collision_dwell(ray)
+    }
+    fn collision_in(&self, ray: &GeometricRay) -> Option<(Collision)> {
+        self.collision_in_dwell(ray, self.dwell)
     }
-    fn collision_in(&self, origin: &Position, direction: &Direction) -> Option<(Collision)> {
-        unimplemented!()
+
+    /// the bounds of a fractal are the bounds of its bounding shape, which
+    /// must contain every copy of the primitive.
+    fn bounds(&self) -> Option<Bounds> {
+        self.bounds.bounds()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::{Direction, Position, Sphere};
+    use nalgebra::Vector3;
+
+    // matches how far bodies move refracted rays into the shape.
+    const SLIGHTLY_OFF_SURFACE: f64 = 0.0001;
+
+    fn inside_from(ray: &GeometricRay, collision: &Collision) -> GeometricRay {
+        GeometricRay::new(
+            collision
+                .collision()
+                .move_along(ray.direction(), SLIGHTLY_OFF_SURFACE),
+            ray.direction().clone(),
+            ray.time(),
+        )
+    }
+
+    /// a sphere of radius 1 with two half sized copies overlapping it either
+    /// side along x.
+    fn two_copies(dwell: u32) -> Fractal<Sphere, Sphere> {
+        let copy =
+            |x| Matrix4::new_translation(&Vector3::new(x, 0.0, 0.0)) * Matrix4::new_scaling(0.5);
+        Fractal::new(
+            Sphere::new(1.0),
+            Sphere::new(2.5),
+            vec![copy(1.2), copy(-1.2)],
+            dwell,
+        )
+    }
+
+    #[test]
+    fn single_sphere_matches_sphere() {
+        let fractal = Fractal::new(Sphere::new(1.0), Sphere::new(1.0), Vec::new(), 3);
+        let sphere = Sphere::new(1.0);
+        let ray = GeometricRay::new(
+            Position::new(0.3, -5.0, 0.2),
+            Direction::new(0.0, 1.0, 0.1),
+            0.0,
+        );
+
+        let entry = fractal.collision(&ray).unwrap();
+        let expected = sphere.collision(&ray).unwrap();
+        assert!((entry.t() - expected.t()).abs() < 1e-9);
+        assert!((entry.normal().dot(expected.normal()) - 1.0).abs() < 1e-9);
+
+        let inside = inside_from(&ray, &entry);
+        let exit = fractal.collision_in(&inside).unwrap();
+        let expected = sphere.collision_in(&inside).unwrap();
+        assert!((exit.t() - expected.t()).abs() < 1e-9);
+        assert!((exit.normal().dot(expected.normal()) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn exit_continues_through_overlapping_copy() {
+        // with a dwell of 1 the copies are solid spheres of radius 1.25. The
+        // ray starts outside the copy on the right, and only reaches it on
+        // leaving the primitive at x = 1.
+        let fractal = two_copies(1);
+        let ray = GeometricRay::new(Position::new(-0.5, 0.0, 0.0), Direction::RIGHT, 0.0);
+        let exit = fractal.collision_in(&ray).unwrap();
+        assert!((exit.t() - 2.95).abs() < 1e-4);
+        assert!((exit.collision().x() - 2.45).abs() < 1e-4);
+    }
+
+    #[test]
+    fn every_entry_has_an_exit() {
+        for dwell in 0..5 {
+            let fractal = two_copies(dwell);
+            for i in 0..50 {
+                for j in 0..50 {
+                    let ray = GeometricRay::new(
+                        Position::new(-5.0, -2.5 + i as f64 * 0.1, -2.5 + j as f64 * 0.1),
+                        Direction::new(1.0, 0.05, 0.02),
+                        0.0,
+                    );
+                    if let Some(entry) = fractal.collision(&ray) {
+                        let inside = inside_from(&ray, &entry);
+                        assert!(fractal.collision_in(&inside).is_some());
+                    }
+                }
+            }
+        }
     }
 }
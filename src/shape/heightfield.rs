@@ -0,0 +1,332 @@
+use crate::image::Image;
+use crate::ray::GeometricRay;
+use crate::shape::{Bounds, Collision, Direction, Position, Shape};
+
+// how far outside a cell a hit on one of its triangles may be, to avoid
+// cracks along the edges between cells.
+const EDGE_TOLERANCE: f64 = 1e-9;
+
+/// Terrain made from a grid of heights, centred on the origin in the xy plane.
+/// Each cell of the grid is split into two triangles, with normals
+/// interpolated smoothly between the grid points. Like Plane, everything
+/// below the surface is inside, so the sides and bottom are open.
+#[derive(Debug)]
+pub struct Heightfield {
+    /// heights row by row, starting at the lowest y.
+    heights: Vec<f64>,
+    normals: Vec<Direction>,
+    /// the lowest and highest point in each cell.
+    cell_ranges: Vec<(f64, f64)>,
+    x_samples: usize,
+    y_samples: usize,
+    width: f64,
+    depth: f64,
+    min_height: f64,
+    max_height: f64,
+}
+
+impl Heightfield {
+    /// heights go row by row from the lowest x and y, with x_samples in each
+    /// row. width and depth are the size along x and y. There must be at
+    /// least 2 samples in each direction.
+    pub fn new(
+        heights: Vec<f64>,
+        x_samples: usize,
+        y_samples: usize,
+        width: f64,
+        depth: f64,
+    ) -> Heightfield {
+        assert!(x_samples >= 2 && y_samples >= 2);
+        assert_eq!(heights.len(), x_samples * y_samples);
+        let cell_x = width / (x_samples - 1) as f64;
+        let cell_y = depth / (y_samples - 1) as f64;
+
+        let height = |x: usize, y: usize| heights[y * x_samples + x];
+        let normals = (0..y_samples)
+            .flat_map(|y| (0..x_samples).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                // central differences, or one sided at the edges.
+                let (left, right) = (x.saturating_sub(1), (x + 1).min(x_samples - 1));
+                let (front, back) = (y.saturating_sub(1), (y + 1).min(y_samples - 1));
+                let slope_x =
+                    (height(right, y) - height(left, y)) / ((right - left) as f64 * cell_x);
+                let slope_y =
+                    (height(x, back) - height(x, front)) / ((back - front) as f64 * cell_y);
+                Direction::new(-slope_x, -slope_y, 1.0).normalise()
+            })
+            .collect();
+
+        let cell_ranges = (0..y_samples - 1)
+            .flat_map(|y| (0..x_samples - 1).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let corners = [
+                    height(x, y),
+                    height(x + 1, y),
+                    height(x, y + 1),
+                    height(x + 1, y + 1),
+                ];
+                (
+                    corners.iter().cloned().fold(f64::INFINITY, f64::min),
+                    corners.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+                )
+            })
+            .collect();
+
+        Heightfield {
+            min_height: heights.iter().cloned().fold(f64::INFINITY, f64::min),
+            max_height: heights.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+            heights,
+            normals,
+            cell_ranges,
+            x_samples,
+            y_samples,
+            width,
+            depth,
+        }
+    }
+
+    /// heights given by a function of x and y, sampled on a grid.
+    pub fn from_function<F>(
+        x_samples: usize,
+        y_samples: usize,
+        width: f64,
+        depth: f64,
+        height: F,
+    ) -> Heightfield
+    where
+        F: Fn(f64, f64) -> f64,
+    {
+        let heights = (0..y_samples)
+            .flat_map(|y| (0..x_samples).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                height(
+                    width * (x as f64 / (x_samples - 1) as f64 - 0.5),
+                    depth * (y as f64 / (y_samples - 1) as f64 - 0.5),
+                )
+            })
+            .collect();
+        Heightfield::new(heights, x_samples, y_samples, width, depth)
+    }
+
+    /// heights taken from the brightness of each pixel of an image, from 0
+    /// for black to height for white. The top of the image is at the highest
+    /// y.
+    pub fn from_image(image: &Image, width: f64, depth: f64, height: f64) -> Heightfield {
+        let (x_samples, y_samples) = (image.width(), image.height());
+        let heights = (0..y_samples)
+            .flat_map(|y| (0..x_samples).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let pixel = image.get(x, y_samples - 1 - y);
+                height * (pixel.red() + pixel.green() + pixel.blue()) / 3.0
+            })
+            .collect();
+        Heightfield::new(
+            heights,
+            x_samples as usize,
+            y_samples as usize,
+            width,
+            depth,
+        )
+    }
+
+    fn grid_point(&self, x: usize, y: usize) -> (Position, &Direction) {
+        let index = y * self.x_samples + x;
+        (
+            Position::new(
+                self.width * (x as f64 / (self.x_samples - 1) as f64 - 0.5),
+                self.depth * (y as f64 / (self.y_samples - 1) as f64 - 0.5),
+                self.heights[index],
+            ),
+            &self.normals[index],
+        )
+    }
+
+    /// the first time the ray crosses the surface within the cell between
+    /// near and far, and whether it is heading down into the terrain.
+    fn cell_collision(
+        &self,
+        ray: &GeometricRay,
+        x: usize,
+        y: usize,
+        near: f64,
+        far: f64,
+    ) -> Option<(Collision, bool)> {
+        let (origin, direction) = (ray.origin(), ray.direction());
+        // skip cells the ray passes entirely above or below.
+        let (low, high) = self.cell_ranges[y * (self.x_samples - 1) + x];
+        let (z1, z2) = (
+            origin.z() + direction.z() * near,
+            origin.z() + direction.z() * far,
+        );
+        if z1.min(z2) > high || z1.max(z2) < low {
+            return None;
+        }
+
+        let corner = self.grid_point(x, y);
+        let right = self.grid_point(x + 1, y);
+        let back = self.grid_point(x, y + 1);
+        let opposite = self.grid_point(x + 1, y + 1);
+        [(&corner, &right, &opposite), (&corner, &opposite, &back)]
+            .iter()
+            .filter_map(|(a, b, c)| triangle_collision(ray, a, b, c))
+            .filter(|(collision, _)| {
+                collision.t() >= near - EDGE_TOLERANCE && collision.t() <= far + EDGE_TOLERANCE
+            })
+            .min_by(|(c1, _), (c2, _)| c1.t().total_cmp(&c2.t()))
+    }
+
+    /// walk through the cells of the grid under the ray in order, returning
+    /// the first crossing of the surface.
+    fn first_collision(&self, ray: &GeometricRay) -> Option<(Collision, bool)> {
+        let (origin, direction) = (ray.origin(), ray.direction());
+        let half_width = self.width / 2.0;
+        let half_depth = self.depth / 2.0;
+
+        // the part of the ray within the bounds of the terrain.
+        let mut near: f64 = 0.0;
+        let mut far = f64::INFINITY;
+        for (start, speed, min, max) in &[
+            (origin.x(), direction.x(), -half_width, half_width),
+            (origin.y(), direction.y(), -half_depth, half_depth),
+            (origin.z(), direction.z(), self.min_height, self.max_height),
+        ] {
+            if *speed == 0.0 {
+                if start < min || start > max {
+                    return None;
+                }
+                continue;
+            }
+            let (t1, t2) = ((min - start) / speed, (max - start) / speed);
+            near = near.max(t1.min(t2));
+            far = far.min(t1.max(t2));
+        }
+        if near > far {
+            return None;
+        }
+
+        let cell_x = self.width / (self.x_samples - 1) as f64;
+        let cell_y = self.depth / (self.y_samples - 1) as f64;
+        let entry = origin.move_along(direction, near);
+        let cell = |position: f64, half: f64, size: f64, cells: usize| {
+            (((position + half) / size).floor().max(0.0) as usize).min(cells - 1)
+        };
+        let mut x = cell(entry.x(), half_width, cell_x, self.x_samples - 1);
+        let mut y = cell(entry.y(), half_depth, cell_y, self.y_samples - 1);
+
+        // the time at which the ray crosses into the next column and row, and
+        // the time taken to cross a whole cell.
+        let step = |speed: f64, cell: usize, half: f64, size: f64, start: f64| {
+            if speed > 0.0 {
+                (
+                    ((cell + 1) as f64 * size - half - start) / speed,
+                    size / speed,
+                )
+            } else if speed < 0.0 {
+                ((cell as f64 * size - half - start) / speed, -size / speed)
+            } else {
+                (f64::INFINITY, f64::INFINITY)
+            }
+        };
+        let (mut next_x, delta_x) = step(direction.x(), x, half_width, cell_x, origin.x());
+        let (mut next_y, delta_y) = step(direction.y(), y, half_depth, cell_y, origin.y());
+
+        let mut cell_near = near;
+        loop {
+            let cell_far = next_x.min(next_y).min(far);
+            if let Some(hit) = self.cell_collision(ray, x, y, cell_near, cell_far) {
+                return Some(hit);
+            }
+            if cell_far >= far {
+                return None;
+            }
+
+            cell_near = cell_far;
+            if next_x < next_y {
+                if direction.x() > 0.0 && x + 1 < self.x_samples - 1 {
+                    x += 1;
+                } else if direction.x() < 0.0 && x > 0 {
+                    x -= 1;
+                } else {
+                    return None;
+                }
+                next_x += delta_x;
+            } else {
+                if direction.y() > 0.0 && y + 1 < self.y_samples - 1 {
+                    y += 1;
+                } else if direction.y() < 0.0 && y > 0 {
+                    y -= 1;
+                } else {
+                    return None;
+                }
+                next_y += delta_y;
+            }
+        }
+    }
+}
+
+/// where a ray crosses a triangle, with the normal interpolated between the
+/// normals at its corners, and whether the ray is heading down through it.
+fn triangle_collision(
+    ray: &GeometricRay,
+    (a, a_normal): &(Position, &Direction),
+    (b, b_normal): &(Position, &Direction),
+    (c, c_normal): &(Position, &Direction),
+) -> Option<(Collision, bool)> {
+    // Möller-Trumbore intersection.
+    let (origin, direction) = (ray.origin(), ray.direction());
+    let edge1 = Direction::from_two_points(a, b);
+    let edge2 = Direction::from_two_points(a, c);
+    let p = direction.cross(&edge2);
+    let determinant = edge1.dot(&p);
+    if determinant == 0.0 {
+        return None;
+    }
+    let to_origin = Direction::from_two_points(a, origin);
+    let u = to_origin.dot(&p) / determinant;
+    let q = to_origin.cross(&edge1);
+    let v = direction.dot(&q) / determinant;
+    if u < -EDGE_TOLERANCE || v < -EDGE_TOLERANCE || u + v > 1.0 + EDGE_TOLERANCE {
+        return None;
+    }
+    let t = edge2.dot(&q) / determinant;
+    if t <= 0.0 {
+        return None;
+    }
+
+    let normal = Direction::new(
+        (1.0 - u - v) * a_normal.x() + u * b_normal.x() + v * c_normal.x(),
+        (1.0 - u - v) * a_normal.y() + u * b_normal.y() + v * c_normal.y(),
+        (1.0 - u - v) * a_normal.z() + u * b_normal.z() + v * c_normal.z(),
+    )
+    .normalise();
+    // the corners go anticlockwise seen from above, so the face normal
+    // points up.
+    let entering = edge1.cross(&edge2).dot(direction) < 0.0;
+    Some((
+        Collision::new(t, normal, origin.move_along(direction, t)),
+        entering,
+    ))
+}
+
+impl Shape for Heightfield {
+    fn collision(&self, ray: &GeometricRay) -> Option<Collision> {
+        match self.first_collision(ray) {
+            Some((collision, true)) => Some(collision),
+            _ => None,
+        }
+    }
+
+    fn collision_in(&self, ray: &GeometricRay) -> Option<Collision> {
+        match self.first_collision(ray) {
+            Some((collision, false)) => Some(collision),
+            _ => None,
+        }
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Some(Bounds::new(
+            Position::new(-self.width / 2.0, -self.depth / 2.0, self.min_height),
+            Position::new(self.width / 2.0, self.depth / 2.0, self.max_height),
+        ))
+    }
+}
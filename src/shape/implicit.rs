@@ -0,0 +1,201 @@
+use crate::ray::GeometricRay;
+use crate::shape::{Bounds, Collision, Position, Shape};
+use std::io;
+
+pub mod expression;
+pub mod interval;
+
+use expression::Expression;
+use interval::Interval;
+
+// pieces of the ray shorter than this are no longer split, and are taken to
+// hold a crossing if the value changes sign across them.
+const MIN_WIDTH: f64 = 1e-5;
+const BISECT_STEPS: u32 = 60;
+
+/// A shape defined by an expression in x, y and z, which is inside where the
+/// expression is negative, such as "x^2 + y^2 + z^2 - 1" for a unit sphere.
+/// Only the part within the bounds is shown.
+///
+/// Rays are split into pieces until interval arithmetic shows each piece
+/// either misses the surface or crosses it exactly once, so thin features
+/// aren't stepped over.
+#[derive(Debug)]
+pub struct Implicit {
+    expression: Expression,
+    bounds: Bounds,
+}
+
+impl Implicit {
+    /// see Expression::parse for what the expression can contain. The
+    /// bounds must be finite, as rays are searched from one side to the
+    /// other.
+    pub fn new(expression: &str, bounds: Bounds) -> io::Result<Implicit> {
+        let finite = [&bounds.min, &bounds.max].iter().all(|corner| {
+            corner.x().is_finite() && corner.y().is_finite() && corner.z().is_finite()
+        });
+        if !finite {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "implicit surface bounds must be finite",
+            ));
+        }
+        Ok(Implicit {
+            expression: Expression::parse(expression)?,
+            bounds,
+        })
+    }
+
+    /// the part of the ray inside the bounds, if any.
+    fn clip(&self, ray: &GeometricRay) -> Option<(f64, f64)> {
+        let (origin, direction) = (ray.origin(), ray.direction());
+        let slab = |origin: f64, direction: f64, min: f64, max: f64| {
+            let (a, b) = ((min - origin) / direction, (max - origin) / direction);
+            if a.is_nan() || b.is_nan() {
+                // the ray runs along a face.
+                (f64::NEG_INFINITY, f64::INFINITY)
+            } else {
+                (a.min(b), a.max(b))
+            }
+        };
+        let (min, max) = (&self.bounds.min, &self.bounds.max);
+        let slabs = [
+            slab(origin.x(), direction.x(), min.x(), max.x()),
+            slab(origin.y(), direction.y(), min.y(), max.y()),
+            slab(origin.z(), direction.z(), min.z(), max.z()),
+        ];
+        let start = slabs.iter().fold(0.0, |start: f64, slab| start.max(slab.0));
+        let end = slabs
+            .iter()
+            .fold(f64::INFINITY, |end: f64, slab| end.min(slab.1));
+        if start < end {
+            Some((start, end))
+        } else {
+            None
+        }
+    }
+
+    /// the first point the expression crosses 0, falling when entering the
+    /// shape or rising when leaving it.
+    fn crossing(&self, ray: &GeometricRay, entering: bool) -> Option<Collision> {
+        let (origin, direction) = (ray.origin(), ray.direction());
+        let (start, end) = self.clip(ray)?;
+        let min_width = MIN_WIDTH / direction.len();
+        let value_at = |t: f64| self.expression.evaluate(&origin.move_along(direction, t));
+        // whether the value crosses 0 the right way from low to high.
+        let crosses = |low: f64, high: f64| {
+            if entering {
+                low > 0.0 && high <= 0.0
+            } else {
+                low < 0.0 && high >= 0.0
+            }
+        };
+
+        // the pieces still to check, with the nearest on top.
+        let mut pieces = vec![(start, end)];
+        while let Some((low, high)) = pieces.pop() {
+            let span = |axis: fn(&Position) -> f64| {
+                let (a, b) = (
+                    axis(&origin.move_along(direction, low)),
+                    axis(&origin.move_along(direction, high)),
+                );
+                Interval::new(a.min(b), a.max(b))
+            };
+            let (value, slope) = self.expression.bound(
+                span(Position::x),
+                span(Position::y),
+                span(Position::z),
+                direction,
+            );
+            if !value.contains(0.0) {
+                continue;
+            }
+
+            let (low_value, high_value) = (value_at(low), value_at(high));
+            // a piece which only rises or only falls crosses 0 at most once.
+            let monotonic = !slope.contains(0.0);
+            if monotonic || high - low < min_width {
+                if crosses(low_value, high_value) {
+                    let t = self.bisect(ray, low, high, entering);
+                    let position = origin.move_along(direction, t);
+                    let normal = self.expression.gradient(&position).normalise();
+                    return Some(Collision::new(t, normal, position));
+                }
+                continue;
+            }
+
+            let middle = 0.5 * (low + high);
+            pieces.push((middle, high));
+            pieces.push((low, middle));
+        }
+        None
+    }
+
+    /// narrow down the crossing between low and high.
+    fn bisect(&self, ray: &GeometricRay, mut low: f64, mut high: f64, entering: bool) -> f64 {
+        let (origin, direction) = (ray.origin(), ray.direction());
+        for _ in 0..BISECT_STEPS {
+            let middle = 0.5 * (low + high);
+            let value = self
+                .expression
+                .evaluate(&origin.move_along(direction, middle));
+            if (value > 0.0) == entering {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        0.5 * (low + high)
+    }
+}
+
+impl Shape for Implicit {
+    fn collision(&self, ray: &GeometricRay) -> Option<Collision> {
+        self.crossing(ray, true)
+    }
+
+    fn collision_in(&self, ray: &GeometricRay) -> Option<Collision> {
+        self.crossing(ray, false)
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Some(self.bounds.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::Direction;
+
+    fn unit_bounds() -> Bounds {
+        Bounds::new(
+            Position::new(-2.0, -2.0, -2.0),
+            Position::new(2.0, 2.0, 2.0),
+        )
+    }
+
+    #[test]
+    fn rejects_infinite_bounds() {
+        let bounds = Bounds::new(
+            Position::new(-1.0, -1.0, f64::NEG_INFINITY),
+            Position::new(1.0, 1.0, 1.0),
+        );
+        assert!(Implicit::new("x^2 + y^2 - 1", bounds).is_err());
+    }
+
+    #[test]
+    fn enters_and_leaves_sphere() {
+        let sphere = Implicit::new("x^2 + y^2 + z^2 = 1", unit_bounds()).unwrap();
+        let ray = GeometricRay::new(Position::new(0.0, -5.0, 0.0), Direction::FORWARDS, 0.0);
+        let hit = sphere.collision(&ray).unwrap();
+        assert!((hit.t() - 4.0).abs() < 1e-9);
+        assert!((hit.normal().y() + 1.0).abs() < 1e-9);
+
+        let inside = GeometricRay::new(Position::ORIGIN, Direction::FORWARDS, 0.0);
+        assert!(sphere.collision(&inside).is_none());
+        let exit = sphere.collision_in(&inside).unwrap();
+        assert!((exit.t() - 1.0).abs() < 1e-9);
+        assert!((exit.normal().y() - 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,397 @@
+use super::interval::Interval;
+use crate::shape::{Direction, Position};
+use nom::types::CompleteStr;
+use nom::{alpha1, double, multispace0};
+use std::io;
+
+/// Functions of a single number.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Function {
+    Sqrt,
+    Abs,
+    Sin,
+    Cos,
+    Exp,
+    Ln,
+}
+
+/// A function of position, built from numbers, the coordinates x, y and z,
+/// arithmetic and a few common functions.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    Constant(f64),
+    X,
+    Y,
+    Z,
+    Negate(Box<Expression>),
+    Add(Box<Expression>, Box<Expression>),
+    Subtract(Box<Expression>, Box<Expression>),
+    Multiply(Box<Expression>, Box<Expression>),
+    Divide(Box<Expression>, Box<Expression>),
+    Power(Box<Expression>, i32),
+    Function(Function, Box<Expression>),
+    Min(Box<Expression>, Box<Expression>),
+    Max(Box<Expression>, Box<Expression>),
+}
+
+impl Expression {
+    /// parse an expression such as "x^2 + y^2 - z^2 - 1". The usual order of
+    /// operations applies, and powers must be whole numbers. An equation
+    /// such as "x^2 + y^2 = 1" gives the left side minus the right.
+    ///
+    /// The functions sqrt, abs, sin, cos, exp and ln take one argument, and
+    /// min and max take two.
+    pub fn parse(text: &str) -> io::Result<Expression> {
+        let invalid = |message| io::Error::new(io::ErrorKind::InvalidInput, message);
+        match equation(CompleteStr(text)) {
+            Ok((CompleteStr(""), expression)) => Ok(expression),
+            Ok((CompleteStr(rest), _)) => Err(invalid(format!("unexpected \"{}\"", rest))),
+            Err(_) => Err(invalid(format!("invalid expression \"{}\"", text))),
+        }
+    }
+
+    /// a function applied to its arguments, if it has the right number of
+    /// them, or a variable if there are no arguments.
+    fn call(name: &str, arguments: Option<Vec<Expression>>) -> Option<Expression> {
+        let mut arguments = match arguments {
+            None => {
+                return match name {
+                    "x" => Some(Expression::X),
+                    "y" => Some(Expression::Y),
+                    "z" => Some(Expression::Z),
+                    _ => None,
+                }
+            }
+            Some(arguments) => arguments.into_iter().map(Box::new),
+        };
+        let (first, second) = (arguments.next(), arguments.next());
+        if arguments.next().is_some() {
+            return None;
+        }
+        let single = |function| match (&first, &second) {
+            (Some(argument), None) => Some(Expression::Function(function, argument.clone())),
+            _ => None,
+        };
+        match name {
+            "sqrt" => single(Function::Sqrt),
+            "abs" => single(Function::Abs),
+            "sin" => single(Function::Sin),
+            "cos" => single(Function::Cos),
+            "exp" => single(Function::Exp),
+            "ln" => single(Function::Ln),
+            "min" => Some(Expression::Min(first?, second?)),
+            "max" => Some(Expression::Max(first?, second?)),
+            _ => None,
+        }
+    }
+
+    pub fn evaluate(&self, point: &Position) -> f64 {
+        match self {
+            Expression::Constant(value) => *value,
+            Expression::X => point.x(),
+            Expression::Y => point.y(),
+            Expression::Z => point.z(),
+            Expression::Negate(a) => -a.evaluate(point),
+            Expression::Add(a, b) => a.evaluate(point) + b.evaluate(point),
+            Expression::Subtract(a, b) => a.evaluate(point) - b.evaluate(point),
+            Expression::Multiply(a, b) => a.evaluate(point) * b.evaluate(point),
+            Expression::Divide(a, b) => a.evaluate(point) / b.evaluate(point),
+            Expression::Power(a, power) => a.evaluate(point).powi(*power),
+            Expression::Function(function, a) => {
+                let a = a.evaluate(point);
+                match function {
+                    Function::Sqrt => a.sqrt(),
+                    Function::Abs => a.abs(),
+                    Function::Sin => a.sin(),
+                    Function::Cos => a.cos(),
+                    Function::Exp => a.exp(),
+                    Function::Ln => a.ln(),
+                }
+            }
+            Expression::Min(a, b) => a.evaluate(point).min(b.evaluate(point)),
+            Expression::Max(a, b) => a.evaluate(point).max(b.evaluate(point)),
+        }
+    }
+
+    /// the range of values over a box of positions, and the range of rates
+    /// at which the value changes moving along direction within the box.
+    pub fn bound(
+        &self,
+        x: Interval,
+        y: Interval,
+        z: Interval,
+        direction: &Direction,
+    ) -> (Interval, Interval) {
+        let bound = |a: &Expression| a.bound(x, y, z, direction);
+        let zero = Interval::point(0.0);
+        match self {
+            Expression::Constant(value) => (Interval::point(*value), zero),
+            Expression::X => (x, Interval::point(direction.x())),
+            Expression::Y => (y, Interval::point(direction.y())),
+            Expression::Z => (z, Interval::point(direction.z())),
+            Expression::Negate(a) => {
+                let (value, slope) = bound(a);
+                (-value, -slope)
+            }
+            Expression::Add(a, b) => {
+                let ((a, a_slope), (b, b_slope)) = (bound(a), bound(b));
+                (a + b, a_slope + b_slope)
+            }
+            Expression::Subtract(a, b) => {
+                let ((a, a_slope), (b, b_slope)) = (bound(a), bound(b));
+                (a - b, a_slope - b_slope)
+            }
+            Expression::Multiply(a, b) => {
+                let ((a, a_slope), (b, b_slope)) = (bound(a), bound(b));
+                (a * b, a_slope * b + a * b_slope)
+            }
+            Expression::Divide(a, b) => {
+                let ((a, a_slope), (b, b_slope)) = (bound(a), bound(b));
+                (a / b, (a_slope * b - a * b_slope) / b.powi(2))
+            }
+            Expression::Power(a, power) => {
+                let (a, slope) = bound(a);
+                let derivative = Interval::point(*power as f64) * a.powi(power - 1);
+                (a.powi(*power), derivative * slope)
+            }
+            Expression::Function(function, a) => {
+                let (a, slope) = bound(a);
+                match function {
+                    Function::Sqrt => {
+                        let root = a.sqrt();
+                        (root, slope / (Interval::point(2.0) * root))
+                    }
+                    Function::Abs => {
+                        let sign = if a.low >= 0.0 {
+                            Interval::point(1.0)
+                        } else if a.high <= 0.0 {
+                            Interval::point(-1.0)
+                        } else {
+                            Interval::new(-1.0, 1.0)
+                        };
+                        (a.abs(), sign * slope)
+                    }
+                    Function::Sin => (a.sin(), a.cos() * slope),
+                    Function::Cos => (a.cos(), -a.sin() * slope),
+                    Function::Exp => (a.exp(), a.exp() * slope),
+                    Function::Ln => (a.ln(), slope / a),
+                }
+            }
+            Expression::Min(a, b) | Expression::Max(a, b) => {
+                let ((a, a_slope), (b, b_slope)) = (bound(a), bound(b));
+                let is_min = matches!(self, Expression::Min(_, _));
+                // where one side is always the smaller, only its slope
+                // counts.
+                let (value, first, second) = if is_min {
+                    (a.min(&b), a.high < b.low, b.high < a.low)
+                } else {
+                    (a.max(&b), a.low > b.high, b.low > a.high)
+                };
+                let slope = if first {
+                    a_slope
+                } else if second {
+                    b_slope
+                } else {
+                    a_slope.hull(&b_slope)
+                };
+                (value, slope)
+            }
+        }
+    }
+
+    /// the gradient of the expression at a point.
+    pub fn gradient(&self, point: &Position) -> Direction {
+        let (x, y, z) = (
+            Interval::point(point.x()),
+            Interval::point(point.y()),
+            Interval::point(point.z()),
+        );
+        let slope = |direction: &Direction| self.bound(x, y, z, direction).1.low;
+        Direction::new(
+            slope(&Direction::RIGHT),
+            slope(&Direction::FORWARDS),
+            slope(&Direction::UP),
+        )
+    }
+}
+
+/// an operator and the expression to its right.
+type Operation = (char, Expression);
+
+/// combine the first expression with each operation in turn, from left to
+/// right.
+fn fold_operations(first: Expression, operations: Vec<Operation>) -> Expression {
+    operations
+        .into_iter()
+        .fold(first, |left, (operator, right)| {
+            let (left, right) = (Box::new(left), Box::new(right));
+            match operator {
+                '+' => Expression::Add(left, right),
+                '-' => Expression::Subtract(left, right),
+                '*' => Expression::Multiply(left, right),
+                _ => Expression::Divide(left, right),
+            }
+        })
+}
+
+named!(equation<CompleteStr, Expression>,
+    do_parse!(
+        left: expression >>
+        right: opt!(preceded!(preceded!(multispace0, char!('=')), expression)) >>
+        multispace0 >>
+        (match right {
+            Some(right) => Expression::Subtract(Box::new(left), Box::new(right)),
+            None => left,
+        })
+    )
+);
+
+named!(expression<CompleteStr, Expression>,
+    do_parse!(
+        first: term >>
+        operations: many0!(pair!(
+            preceded!(multispace0, one_of!("+-")),
+            term
+        )) >>
+        (fold_operations(first, operations))
+    )
+);
+
+named!(term<CompleteStr, Expression>,
+    do_parse!(
+        first: unary >>
+        operations: many0!(pair!(
+            preceded!(multispace0, one_of!("*/")),
+            unary
+        )) >>
+        (fold_operations(first, operations))
+    )
+);
+
+named!(unary<CompleteStr, Expression>,
+    preceded!(multispace0, alt!(
+        map!(preceded!(char!('-'), unary), |a| Expression::Negate(Box::new(a))) |
+        power
+    ))
+);
+
+named!(power<CompleteStr, Expression>,
+    do_parse!(
+        base: atom >>
+        exponent: opt!(preceded!(
+            delimited!(multispace0, char!('^'), multispace0),
+            map_opt!(double, |power: f64| if power.fract() == 0.0 {
+                Some(power as i32)
+            } else {
+                None
+            })
+        )) >>
+        (match exponent {
+            Some(exponent) => Expression::Power(Box::new(base), exponent),
+            None => base,
+        })
+    )
+);
+
+named!(atom<CompleteStr, Expression>,
+    preceded!(multispace0, alt!(
+        map!(double, Expression::Constant) |
+        delimited!(char!('('), expression, preceded!(multispace0, char!(')'))) |
+        call
+    ))
+);
+
+named!(call<CompleteStr, Expression>,
+    map_opt!(
+        pair!(
+            alpha1,
+            opt!(preceded!(multispace0, delimited!(
+                char!('('),
+                separated_nonempty_list!(preceded!(multispace0, char!(',')), expression),
+                preceded!(multispace0, char!(')'))
+            )))
+        ),
+        |(name, arguments): (CompleteStr, Option<Vec<Expression>>)| Expression::call(&name, arguments)
+    )
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn boxed(expression: Expression) -> Box<Expression> {
+        Box::new(expression)
+    }
+
+    #[test]
+    fn parses_doc_examples() {
+        let x2_y2 = Expression::Add(
+            boxed(Expression::Power(boxed(Expression::X), 2)),
+            boxed(Expression::Power(boxed(Expression::Y), 2)),
+        );
+        assert_eq!(
+            Expression::parse("x^2 + y^2 - z^2 - 1").unwrap(),
+            Expression::Subtract(
+                boxed(Expression::Subtract(
+                    boxed(x2_y2.clone()),
+                    boxed(Expression::Power(boxed(Expression::Z), 2)),
+                )),
+                boxed(Expression::Constant(1.0)),
+            )
+        );
+        let equation = Expression::Subtract(boxed(x2_y2), boxed(Expression::Constant(1.0)));
+        for text in &[
+            "x^2 + y^2 = 1",
+            "x^2 + y^2 =1",
+            "x^2+y^2=1",
+            " x^2 + y^2 = 1 ",
+        ] {
+            assert_eq!(Expression::parse(text).unwrap(), equation);
+        }
+    }
+
+    #[test]
+    fn follows_order_of_operations() {
+        assert_eq!(
+            Expression::parse("-x^2").unwrap(),
+            Expression::Negate(boxed(Expression::Power(boxed(Expression::X), 2)))
+        );
+        assert_eq!(
+            Expression::parse("1 + 2 * 3").unwrap(),
+            Expression::Add(
+                boxed(Expression::Constant(1.0)),
+                boxed(Expression::Multiply(
+                    boxed(Expression::Constant(2.0)),
+                    boxed(Expression::Constant(3.0)),
+                )),
+            )
+        );
+        let point = Position::new(2.0, 3.0, 5.0);
+        let value = |text| Expression::parse(text).unwrap().evaluate(&point);
+        assert_eq!(value("x - y - z"), -6.0);
+        assert_eq!(value("z / x / 5"), 0.5);
+        assert_eq!(value("(x + y) * z"), 25.0);
+        assert_eq!(value("min(x, y) + max(y, z) + abs(-x)"), 9.0);
+    }
+
+    #[test]
+    fn rejects_wrong_argument_counts() {
+        for text in &[
+            "sqrt(x, y)",
+            "min(x)",
+            "max(x, y, z)",
+            "x(y)",
+            "sin",
+            "foo(x)",
+        ] {
+            assert!(Expression::parse(text).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn rejects_trailing_input() {
+        assert!(Expression::parse("x + ").is_err());
+        assert!(Expression::parse("x^1.5").is_err());
+        assert!(Expression::parse("(x").is_err());
+    }
+}
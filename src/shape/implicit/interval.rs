@@ -0,0 +1,150 @@
+use std::f64::consts::PI;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// A range of numbers, with arithmetic giving a range containing every
+/// possible result of the operation on numbers from the ranges. Ranges may
+/// be bigger than necessary, but never miss a result.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+    pub low: f64,
+    pub high: f64,
+}
+
+impl Interval {
+    pub const ENTIRE: Interval = Interval {
+        low: f64::NEG_INFINITY,
+        high: f64::INFINITY,
+    };
+
+    pub fn new(low: f64, high: f64) -> Interval {
+        Interval { low, high }
+    }
+
+    /// the interval containing only x.
+    pub fn point(x: f64) -> Interval {
+        Interval { low: x, high: x }
+    }
+
+    pub fn contains(&self, x: f64) -> bool {
+        self.low <= x && x <= self.high
+    }
+
+    /// the smallest interval containing both.
+    pub fn hull(&self, other: &Interval) -> Interval {
+        Interval::new(self.low.min(other.low), self.high.max(other.high))
+    }
+
+    pub fn min(&self, other: &Interval) -> Interval {
+        Interval::new(self.low.min(other.low), self.high.min(other.high))
+    }
+
+    pub fn max(&self, other: &Interval) -> Interval {
+        Interval::new(self.low.max(other.low), self.high.max(other.high))
+    }
+
+    pub fn abs(&self) -> Interval {
+        if self.low >= 0.0 {
+            *self
+        } else if self.high <= 0.0 {
+            -*self
+        } else {
+            Interval::new(0.0, self.high.max(-self.low))
+        }
+    }
+
+    pub fn powi(&self, power: i32) -> Interval {
+        if power < 0 {
+            return Interval::point(1.0) / self.powi(-power);
+        }
+        if power % 2 == 1 {
+            // odd powers only rise.
+            return Interval::new(self.low.powi(power), self.high.powi(power));
+        }
+        let magnitude = self.abs();
+        Interval::new(magnitude.low.powi(power), magnitude.high.powi(power))
+    }
+
+    /// negative numbers are ignored, as they have no square root.
+    pub fn sqrt(&self) -> Interval {
+        Interval::new(self.low.max(0.0).sqrt(), self.high.max(0.0).sqrt())
+    }
+
+    pub fn exp(&self) -> Interval {
+        Interval::new(self.low.exp(), self.high.exp())
+    }
+
+    /// negative numbers are ignored, as they have no logarithm.
+    pub fn ln(&self) -> Interval {
+        Interval::new(self.low.max(0.0).ln(), self.high.max(0.0).ln())
+    }
+
+    pub fn sin(&self) -> Interval {
+        if self.high - self.low >= 2.0 * PI {
+            return Interval::new(-1.0, 1.0);
+        }
+        // whether the interval contains the angle plus any number of turns.
+        let reaches = |angle: f64| {
+            let turns = ((self.low - angle) / (2.0 * PI)).ceil();
+            angle + turns * 2.0 * PI <= self.high
+        };
+        let (a, b) = (self.low.sin(), self.high.sin());
+        Interval::new(
+            if reaches(-PI / 2.0) { -1.0 } else { a.min(b) },
+            if reaches(PI / 2.0) { 1.0 } else { a.max(b) },
+        )
+    }
+
+    pub fn cos(&self) -> Interval {
+        (*self + Interval::point(PI / 2.0)).sin()
+    }
+}
+
+impl Add for Interval {
+    type Output = Interval;
+    fn add(self, other: Interval) -> Interval {
+        Interval::new(self.low + other.low, self.high + other.high)
+    }
+}
+
+impl Sub for Interval {
+    type Output = Interval;
+    fn sub(self, other: Interval) -> Interval {
+        Interval::new(self.low - other.high, self.high - other.low)
+    }
+}
+
+impl Neg for Interval {
+    type Output = Interval;
+    fn neg(self) -> Interval {
+        Interval::new(-self.high, -self.low)
+    }
+}
+
+impl Mul for Interval {
+    type Output = Interval;
+    fn mul(self, other: Interval) -> Interval {
+        // 0 times infinity is taken to be 0, since the infinite end of an
+        // interval is never actually reached.
+        let product = |a: f64, b: f64| if a == 0.0 || b == 0.0 { 0.0 } else { a * b };
+        let products = [
+            product(self.low, other.low),
+            product(self.low, other.high),
+            product(self.high, other.low),
+            product(self.high, other.high),
+        ];
+        Interval::new(
+            products.iter().cloned().fold(f64::INFINITY, f64::min),
+            products.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+        )
+    }
+}
+
+impl Div for Interval {
+    type Output = Interval;
+    fn div(self, other: Interval) -> Interval {
+        if other.contains(0.0) {
+            return Interval::ENTIRE;
+        }
+        self * Interval::new(1.0 / other.high, 1.0 / other.low)
+    }
+}
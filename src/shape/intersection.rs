@@ -1,4 +1,5 @@
-use crate::shape::{Collision, Direction, Position, Shape};
+use crate::ray::GeometricRay;
+use crate::shape::{Bounds, Collision, Shape};
 
 pub struct Intersection<T, U> {
     shape1: T,
@@ -15,10 +16,10 @@ impl<T: Shape, U: Shape> Intersection<T, U> {
 }
 
 impl<T: Shape, U: Shape> Shape for Intersection<T, U> {
-    fn collision(&self, origin: &Position, direction: &Direction) -> Option<(Collision)> {
-        let collision1 = self.shape1.collision(origin, direction);
+    fn collision(&self, ray: &GeometricRay) -> Option<(Collision)> {
+        let collision1 = self.shape1.collision(ray);
         // maybe do a check here to see if we can short circuit?
-        let collision2 = self.shape2.collision(origin, direction);
+        let collision2 = self.shape2.collision(ray);
 
         match (collision1, collision2) {
             (Some(collision1), Some(collision2)) => {
@@ -36,10 +37,10 @@ impl<T: Shape, U: Shape> Shape for Intersection<T, U> {
         }
     }
 
-    fn collision_in(&self, origin: &Position, direction: &Direction) -> Option<(Collision)> {
-        let collision1 = self.shape1.collision_in(origin, direction);
+    fn collision_in(&self, ray: &GeometricRay) -> Option<(Collision)> {
+        let collision1 = self.shape1.collision_in(ray);
         // maybe do a check here to see if we can short circuit?
-        let collision2 = self.shape2.collision_in(origin, direction);
+        let collision2 = self.shape2.collision_in(ray);
 
         match (collision1, collision2) {
             (Some(collision1), Some(collision2)) => {
@@ -56,4 +57,13 @@ impl<T: Shape, U: Shape> Shape for Intersection<T, U> {
             _ => None,
         }
     }
+
+    fn bounds(&self) -> Option<Bounds> {
+        match (self.shape1.bounds(), self.shape2.bounds()) {
+            // if the bounds do not overlap the shape is empty, so any bounds
+            // will do.
+            (Some(bounds1), Some(bounds2)) => bounds1.intersection(&bounds2).or(Some(bounds1)),
+            (bounds1, bounds2) => bounds1.or(bounds2),
+        }
+    }
 }
@@ -1,14 +1,17 @@
 use crate::collision::Collision;
+use crate::ray::GeometricRay;
 use crate::shape::{Direction, Position, Shape};
 
+pub mod combine;
 pub mod cuboid;
+pub mod domain;
+pub mod mandelbox;
+pub mod mandelbulb;
+pub mod menger;
+pub mod sierpinski;
 pub mod sphere;
 
 pub trait Marcher {
-    const EPSILON: f64 = 0.00001;
-    const NORMAL_EPSILON: f64 = 0.001;
-    const DRAW_DIST: f64 = 100.0;
-
     fn inside(&self, point: &Position) -> bool {
         self.distance_estimator(point) < 0.0
     }
@@ -17,12 +20,13 @@ pub trait Marcher {
     fn distance_estimator(&self, point: &Position) -> f64;
 
     /// Returns the normal of a point at the surface of a shape defined by a
-    /// distance estimator. If you are implementing your own method, ensure that
-    /// the normal is normalised.
-    fn get_normal(&self, point: &Position) -> Direction {
-        let dx = Position::ORIGIN.move_along(&Direction::RIGHT, Self::NORMAL_EPSILON);
-        let dy = Position::ORIGIN.move_along(&Direction::FORWARDS, Self::NORMAL_EPSILON);
-        let dz = Position::ORIGIN.move_along(&Direction::UP, Self::NORMAL_EPSILON);
+    /// distance estimator, sampling the distance epsilon either side of the
+    /// point. If you are implementing your own method, ensure that the normal
+    /// is normalised.
+    fn get_normal(&self, point: &Position, epsilon: f64) -> Direction {
+        let dx = Position::ORIGIN.move_along(&Direction::RIGHT, epsilon);
+        let dy = Position::ORIGIN.move_along(&Direction::FORWARDS, epsilon);
+        let dz = Position::ORIGIN.move_along(&Direction::UP, epsilon);
         Direction::new(
             self.distance_estimator(&(point + &dx)) - self.distance_estimator(&(point - &dx)),
             self.distance_estimator(&(point + &dy)) - self.distance_estimator(&(point - &dy)),
@@ -32,60 +36,183 @@ pub trait Marcher {
     }
 }
 
-pub struct MarchShape<T>(pub T);
+/// How a MarchShape steps along rays.
+#[derive(Debug, Clone, Copy)]
+pub struct MarchSettings {
+    /// a ray has hit the surface once it is this close.
+    pub epsilon: f64,
+    /// how far either side of a hit the distance is sampled to find the
+    /// normal, for shapes without their own normals.
+    pub normal_epsilon: f64,
+    /// how much both epsilons grow for each unit the ray has travelled. Far
+    /// away detail is smaller than a pixel, so this avoids marching into it,
+    /// which is slow and gives noisy normals. About the angle covered by a
+    /// pixel works well.
+    pub relative_epsilon: f64,
+    /// rays which travel this far without hitting anything miss.
+    pub draw_distance: f64,
+    /// rays which take this many steps without hitting anything miss, so a
+    /// poor distance estimator can't get stuck.
+    pub max_steps: u32,
+    /// each step goes this many times the distance estimate, between 1 and 2.
+    /// Longer steps reach the surface quicker, and steps which overshoot are
+    /// taken again without relaxation.
+    pub relaxation: f64,
+}
 
-impl<T: Marcher> Marcher for MarchShape<T> {
-    const EPSILON: f64 = T::EPSILON;
-    const NORMAL_EPSILON: f64 = T::NORMAL_EPSILON;
-    const DRAW_DIST: f64 = T::DRAW_DIST;
-    fn distance_estimator(&self, point: &Position) -> f64 {
-        self.0.distance_estimator(point)
-    }
-    fn get_normal(&self, point: &Position) -> Direction {
-        self.0.get_normal(point)
-    }
+/// A shape made from a distance estimator, found by stepping along rays as
+/// far as the distance estimate allows until they reach its surface.
+pub struct MarchShape<T> {
+    shape: T,
+    settings: MarchSettings,
 }
 
-impl<T: Marcher> Shape for MarchShape<T> {
-    fn collision(&self, origin: &Position, direction: &Direction) -> Option<Collision> {
-        if self.inside(origin) {
-            return None;
-        }
+impl<T: Marcher> MarchShape<T> {
+    pub fn new(shape: T, settings: MarchSettings) -> MarchShape<T> {
+        assert!(1.0 <= settings.relaxation && settings.relaxation < 2.0);
+        MarchShape { shape, settings }
+    }
 
-        let mut t = 0.0;
+    /// step along the ray until it reaches the surface. side is 1 to march
+    /// from outside the shape, or -1 from inside.
+    fn march(&self, ray: &GeometricRay, side: f64) -> Option<Collision> {
+        let settings = &self.settings;
+        let (origin, direction) = (ray.origin(), ray.direction());
         let normalised = direction.normalise();
 
-        loop {
+        let mut t = 0.0;
+        let mut relaxation = settings.relaxation;
+        // the previous step, and the distance estimate where it started.
+        let mut step: f64 = 0.0;
+        let mut previous_distance: f64 = 0.0;
+        for _ in 0..settings.max_steps {
             let position = origin.move_along(&normalised, t);
-            let safe_dist = self.distance_estimator(&position);
-            t += safe_dist;
+            let distance = side * self.distance_estimator(&position);
 
-            if t > Self::DRAW_DIST {
-                return None;
+            // the spheres the distance estimates guarantee are empty should
+            // overlap. If they don't, the relaxed step may have skipped over
+            // the surface, so go back and take the plain step instead.
+            if relaxation > 1.0 && distance.abs() + previous_distance < step {
+                t += previous_distance - step;
+                step = previous_distance;
+                relaxation = 1.0;
+                continue;
+            }
+
+            let threshold = settings.epsilon + settings.relative_epsilon * t;
+            if distance < threshold {
+                let normal_epsilon = settings.normal_epsilon + settings.relative_epsilon * t;
+                return Some(Collision::new(
+                    // the ray's direction may not be normalised.
+                    t / direction.len(),
+                    self.get_normal(&position, normal_epsilon),
+                    position,
+                ));
             }
-            if safe_dist < Self::EPSILON {
-                return Some(Collision::new(t, self.get_normal(&position), position));
+
+            step = distance * relaxation;
+            previous_distance = distance;
+            t += step;
+            if t > settings.draw_distance {
+                return None;
             }
         }
+        None
     }
-    fn collision_in(&self, origin: &Position, direction: &Direction) -> Option<Collision> {
-        if !self.inside(origin) {
+}
+
+impl<T: Marcher> Marcher for MarchShape<T> {
+    fn distance_estimator(&self, point: &Position) -> f64 {
+        self.shape.distance_estimator(point)
+    }
+    fn get_normal(&self, point: &Position, epsilon: f64) -> Direction {
+        self.shape.get_normal(point, epsilon)
+    }
+}
+
+impl<T: Marcher> Shape for MarchShape<T> {
+    fn collision(&self, ray: &GeometricRay) -> Option<Collision> {
+        if self.inside(ray.origin()) {
             return None;
         }
+        self.march(ray, 1.0)
+    }
+    fn collision_in(&self, ray: &GeometricRay) -> Option<Collision> {
+        if !self.inside(ray.origin()) {
+            return None;
+        }
+        self.march(ray, -1.0)
+    }
+}
 
-        let mut t = 0.0;
-        let normalised = direction.normalise();
+#[cfg(test)]
+pub mod tests {
+    use super::*;
 
-        loop {
-            let position = origin.move_along(&normalised, t);
-            let safe_dist = -self.distance_estimator(&position);
-            t += safe_dist;
+    /// check the distance estimate is never more than the distance to the
+    /// surface, found by brute force. The surface is taken to be where the
+    /// estimate changes sign between neighbouring points of a grid with the
+    /// given step, covering a cube extent either side of the origin.
+    pub fn assert_distance_bound<T: Marcher>(shape: &T, extent: f64, step: f64) {
+        let count = (2.0 * extent / step).round() as usize + 1;
+        let position = |i: usize, j: usize, k: usize| {
+            Position::new(
+                -extent + i as f64 * step,
+                -extent + j as f64 * step,
+                -extent + k as f64 * step,
+            )
+        };
+        let index = |i: usize, j: usize, k: usize| (i * count + j) * count + k;
+        let mut values = Vec::with_capacity(count * count * count);
+        for i in 0..count {
+            for j in 0..count {
+                for k in 0..count {
+                    values.push(shape.distance_estimator(&position(i, j, k)));
+                }
+            }
+        }
 
-            if t > Self::DRAW_DIST {
-                return None;
+        let mut surface = Vec::new();
+        for i in 0..count {
+            for j in 0..count {
+                for k in 0..count {
+                    let a = values[index(i, j, k)];
+                    let neighbours = [(i + 1, j, k), (i, j + 1, k), (i, j, k + 1)];
+                    for &(ni, nj, nk) in &neighbours {
+                        if ni == count || nj == count || nk == count {
+                            continue;
+                        }
+                        let b = values[index(ni, nj, nk)];
+                        if (a < 0.0) != (b < 0.0) {
+                            let fraction = (a / (a - b)).clamp(0.0, 1.0);
+                            let (p, q) = (position(i, j, k), position(ni, nj, nk));
+                            surface
+                                .push(p.move_along(&Direction::from_two_points(&p, &q), fraction));
+                        }
+                    }
+                }
             }
-            if safe_dist < Self::EPSILON {
-                return Some(Collision::new(t, self.get_normal(&position), position));
+        }
+        assert!(!surface.is_empty());
+
+        // every sampled surface point is within a step of the real surface.
+        for i in (0..count).step_by(5) {
+            for j in (0..count).step_by(5) {
+                for k in (0..count).step_by(5) {
+                    let point = position(i, j, k);
+                    let distance = surface
+                        .iter()
+                        .map(|surface| Direction::from_two_points(&point, surface).len())
+                        .fold(f64::INFINITY, f64::min);
+                    let estimate = values[index(i, j, k)].abs();
+                    assert!(
+                        estimate <= distance + step,
+                        "estimate {} at {:?} is more than the distance {}",
+                        estimate,
+                        point,
+                        distance
+                    );
+                }
             }
         }
     }
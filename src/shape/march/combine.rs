@@ -0,0 +1,280 @@
+use super::Marcher;
+use crate::shape::{Direction, Position};
+
+/// Everything inside either shape.
+pub struct Union<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A: Marcher, B: Marcher> Union<A, B> {
+    pub fn new(a: A, b: B) -> Union<A, B> {
+        Union { a, b }
+    }
+}
+
+impl<A: Marcher, B: Marcher> Marcher for Union<A, B> {
+    fn distance_estimator(&self, point: &Position) -> f64 {
+        self.a
+            .distance_estimator(point)
+            .min(self.b.distance_estimator(point))
+    }
+
+    fn get_normal(&self, point: &Position, epsilon: f64) -> Direction {
+        if self.a.distance_estimator(point) < self.b.distance_estimator(point) {
+            self.a.get_normal(point, epsilon)
+        } else {
+            self.b.get_normal(point, epsilon)
+        }
+    }
+}
+
+/// Everything inside both shapes.
+pub struct Intersection<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A: Marcher, B: Marcher> Intersection<A, B> {
+    pub fn new(a: A, b: B) -> Intersection<A, B> {
+        Intersection { a, b }
+    }
+}
+
+impl<A: Marcher, B: Marcher> Marcher for Intersection<A, B> {
+    fn distance_estimator(&self, point: &Position) -> f64 {
+        self.a
+            .distance_estimator(point)
+            .max(self.b.distance_estimator(point))
+    }
+
+    fn get_normal(&self, point: &Position, epsilon: f64) -> Direction {
+        if self.a.distance_estimator(point) > self.b.distance_estimator(point) {
+            self.a.get_normal(point, epsilon)
+        } else {
+            self.b.get_normal(point, epsilon)
+        }
+    }
+}
+
+/// Everything inside a but not inside b.
+pub struct Subtraction<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A: Marcher, B: Marcher> Subtraction<A, B> {
+    pub fn new(a: A, b: B) -> Subtraction<A, B> {
+        Subtraction { a, b }
+    }
+}
+
+impl<A: Marcher, B: Marcher> Marcher for Subtraction<A, B> {
+    fn distance_estimator(&self, point: &Position) -> f64 {
+        self.a
+            .distance_estimator(point)
+            .max(-self.b.distance_estimator(point))
+    }
+
+    fn get_normal(&self, point: &Position, epsilon: f64) -> Direction {
+        if self.a.distance_estimator(point) > -self.b.distance_estimator(point) {
+            self.a.get_normal(point, epsilon)
+        } else {
+            // the surface of b faces into the hole.
+            self.b.get_normal(point, epsilon).negate()
+        }
+    }
+}
+
+/// the polynomial smooth minimum of a and b, which blends between them where
+/// they are within radius of each other. It is never more than the minimum,
+/// so it is still a bound on the distance.
+fn smooth_min(a: f64, b: f64, radius: f64) -> f64 {
+    if radius <= 0.0 {
+        return a.min(b);
+    }
+    let h = (radius - (a - b).abs()).max(0.0) / radius;
+    a.min(b) - h * h * radius / 4.0
+}
+
+/// A union which fills in the creases where the shapes meet, blending them
+/// together within radius of each other.
+pub struct SmoothUnion<A, B> {
+    a: A,
+    b: B,
+    radius: f64,
+}
+
+impl<A: Marcher, B: Marcher> SmoothUnion<A, B> {
+    pub fn new(a: A, b: B, radius: f64) -> SmoothUnion<A, B> {
+        SmoothUnion { a, b, radius }
+    }
+}
+
+impl<A: Marcher, B: Marcher> Marcher for SmoothUnion<A, B> {
+    fn distance_estimator(&self, point: &Position) -> f64 {
+        smooth_min(
+            self.a.distance_estimator(point),
+            self.b.distance_estimator(point),
+            self.radius,
+        )
+    }
+}
+
+/// An intersection with the edges where the shapes meet rounded off within
+/// radius of each other.
+pub struct SmoothIntersection<A, B> {
+    a: A,
+    b: B,
+    radius: f64,
+}
+
+impl<A: Marcher, B: Marcher> SmoothIntersection<A, B> {
+    pub fn new(a: A, b: B, radius: f64) -> SmoothIntersection<A, B> {
+        SmoothIntersection { a, b, radius }
+    }
+}
+
+impl<A: Marcher, B: Marcher> Marcher for SmoothIntersection<A, B> {
+    fn distance_estimator(&self, point: &Position) -> f64 {
+        -smooth_min(
+            -self.a.distance_estimator(point),
+            -self.b.distance_estimator(point),
+            self.radius,
+        )
+    }
+}
+
+/// A subtraction with the edges of the hole rounded off within radius.
+pub struct SmoothSubtraction<A, B> {
+    a: A,
+    b: B,
+    radius: f64,
+}
+
+impl<A: Marcher, B: Marcher> SmoothSubtraction<A, B> {
+    pub fn new(a: A, b: B, radius: f64) -> SmoothSubtraction<A, B> {
+        SmoothSubtraction { a, b, radius }
+    }
+}
+
+impl<A: Marcher, B: Marcher> Marcher for SmoothSubtraction<A, B> {
+    fn distance_estimator(&self, point: &Position) -> f64 {
+        -smooth_min(
+            -self.a.distance_estimator(point),
+            self.b.distance_estimator(point),
+            self.radius,
+        )
+    }
+}
+
+/// The shape grown outwards by radius, which rounds off its corners and
+/// edges. Shrink the shape by radius first to keep it the same size.
+pub struct Round<T> {
+    shape: T,
+    radius: f64,
+}
+
+impl<T: Marcher> Round<T> {
+    pub fn new(shape: T, radius: f64) -> Round<T> {
+        Round { shape, radius }
+    }
+}
+
+impl<T: Marcher> Marcher for Round<T> {
+    fn distance_estimator(&self, point: &Position) -> f64 {
+        self.shape.distance_estimator(point) - self.radius
+    }
+}
+
+/// A hollow shell thickness thick, centred on the surface of the shape.
+/// Onions can be nested to give several layers.
+pub struct Onion<T> {
+    shape: T,
+    thickness: f64,
+}
+
+impl<T: Marcher> Onion<T> {
+    pub fn new(shape: T, thickness: f64) -> Onion<T> {
+        Onion { shape, thickness }
+    }
+}
+
+impl<T: Marcher> Marcher for Onion<T> {
+    fn distance_estimator(&self, point: &Position) -> f64 {
+        self.shape.distance_estimator(point).abs() - self.thickness / 2.0
+    }
+}
+
+/// The surface of the shape pushed out by a function of position, such as a
+/// noise or ripple pattern. lipschitz is the most the displacement can change
+/// per unit distance, which is used to keep the distance estimate a bound.
+pub struct Displace<T, F> {
+    shape: T,
+    displacement: F,
+    lipschitz: f64,
+}
+
+impl<T: Marcher, F: Fn(&Position) -> f64> Displace<T, F> {
+    pub fn new(shape: T, displacement: F, lipschitz: f64) -> Displace<T, F> {
+        assert!(lipschitz >= 0.0);
+        Displace {
+            shape,
+            displacement,
+            lipschitz,
+        }
+    }
+}
+
+impl<T: Marcher, F: Fn(&Position) -> f64> Marcher for Displace<T, F> {
+    fn distance_estimator(&self, point: &Position) -> f64 {
+        (self.shape.distance_estimator(point) - (self.displacement)(point)) / (1.0 + self.lipschitz)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::march::cuboid::Cuboid;
+    use crate::shape::march::domain::Affine;
+    use crate::shape::march::sphere::Sphere;
+    use crate::shape::march::tests::assert_distance_bound;
+    use nalgebra::base::{Matrix4, Vector3};
+
+    /// a pair of overlapping shapes to combine.
+    fn pair() -> (Affine<Sphere>, Cuboid) {
+        let sphere = Affine::new(
+            Sphere::new(0.7),
+            Matrix4::new_translation(&Vector3::new(0.5, 0.2, 0.0)),
+        );
+        (sphere, Cuboid::new(1.2, 1.0, 0.8))
+    }
+
+    #[test]
+    fn booleans_are_bounds() {
+        let (a, b) = pair();
+        assert_distance_bound(&Union::new(a, b), 1.5, 0.05);
+        let (a, b) = pair();
+        assert_distance_bound(&Intersection::new(a, b), 1.5, 0.05);
+        let (a, b) = pair();
+        assert_distance_bound(&Subtraction::new(b, a), 1.5, 0.05);
+    }
+
+    #[test]
+    fn smooth_booleans_are_bounds() {
+        let (a, b) = pair();
+        assert_distance_bound(&SmoothUnion::new(a, b, 0.3), 1.5, 0.05);
+        let (a, b) = pair();
+        assert_distance_bound(&SmoothIntersection::new(a, b, 0.3), 1.5, 0.05);
+        let (a, b) = pair();
+        assert_distance_bound(&SmoothSubtraction::new(b, a, 0.3), 1.5, 0.05);
+    }
+
+    #[test]
+    fn modifiers_are_bounds() {
+        assert_distance_bound(&Round::new(Cuboid::new(1.0, 1.4, 0.6), 0.2), 1.5, 0.05);
+        assert_distance_bound(&Onion::new(Sphere::new(1.0), 0.2), 1.5, 0.05);
+        let ripples = |point: &Position| 0.1 * (5.0 * point.x()).sin();
+        assert_distance_bound(&Displace::new(Sphere::new(1.0), ripples, 0.5), 1.5, 0.05);
+    }
+}
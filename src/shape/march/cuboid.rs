@@ -18,13 +18,16 @@ impl Cuboid {
 }
 
 impl Marcher for Cuboid {
+    /// the exact distance, so rounding the cuboid rounds its edges.
     fn distance_estimator(&self, point: &Position) -> f64 {
-        (point.x().abs() - self.half_x)
-            .max(point.y().abs() - self.half_y)
-            .max(point.z().abs() - self.half_z)
+        let x = point.x().abs() - self.half_x;
+        let y = point.y().abs() - self.half_y;
+        let z = point.z().abs() - self.half_z;
+        let outside = Direction::new(x.max(0.0), y.max(0.0), z.max(0.0)).len();
+        outside + x.max(y).max(z).min(0.0)
     }
 
-    fn get_normal(&self, point: &Position) -> Direction {
+    fn get_normal(&self, point: &Position, _epsilon: f64) -> Direction {
         let x = point.x();
         let y = point.y();
         let z = point.z();
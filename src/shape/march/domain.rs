@@ -0,0 +1,282 @@
+use super::Marcher;
+use crate::shape::{Direction, Position};
+use nalgebra::base::{Matrix3, Matrix4};
+
+/// the offset of x from the nearest multiple of spacing. A spacing of 0
+/// leaves x alone.
+fn wrap(x: f64, spacing: f64) -> f64 {
+    if spacing == 0.0 {
+        x
+    } else {
+        x - spacing * (x / spacing).round()
+    }
+}
+
+/// Copies of the shape repeated forever on a grid, spacing apart along each
+/// axis. A spacing of 0 doesn't repeat along that axis. The shape should fit
+/// inside one cell of the grid, centred on the origin, or the distance
+/// estimate can overshoot into the neighbouring copies.
+pub struct Repeat<T> {
+    shape: T,
+    spacing: [f64; 3],
+}
+
+impl<T: Marcher> Repeat<T> {
+    pub fn new(shape: T, spacing: [f64; 3]) -> Repeat<T> {
+        assert!(spacing.iter().all(|&spacing| spacing >= 0.0));
+        Repeat { shape, spacing }
+    }
+}
+
+impl<T: Marcher> Marcher for Repeat<T> {
+    fn distance_estimator(&self, point: &Position) -> f64 {
+        self.shape.distance_estimator(&Position::new(
+            wrap(point.x(), self.spacing[0]),
+            wrap(point.y(), self.spacing[1]),
+            wrap(point.z(), self.spacing[2]),
+        ))
+    }
+}
+
+/// A grid of copies of the shape, spacing apart along each axis with the given
+/// number of copies along each, centred on the origin.
+pub struct RepeatFinite<T> {
+    shape: T,
+    spacing: [f64; 3],
+    copies: [u32; 3],
+}
+
+impl<T: Marcher> RepeatFinite<T> {
+    pub fn new(shape: T, spacing: [f64; 3], copies: [u32; 3]) -> RepeatFinite<T> {
+        assert!(spacing.iter().all(|&spacing| spacing >= 0.0));
+        assert!(copies.iter().all(|&copies| copies >= 1));
+        RepeatFinite {
+            shape,
+            spacing,
+            copies,
+        }
+    }
+}
+
+impl<T: Marcher> Marcher for RepeatFinite<T> {
+    fn distance_estimator(&self, point: &Position) -> f64 {
+        // the offset from the nearest copy, counting from the copy at the
+        // lowest end of the row.
+        let wrap_finite = |x: f64, spacing: f64, copies: u32| {
+            if spacing == 0.0 {
+                return x;
+            }
+            let middle = (copies - 1) as f64 / 2.0;
+            let index = (x / spacing + middle)
+                .round()
+                .clamp(0.0, (copies - 1) as f64);
+            x - spacing * (index - middle)
+        };
+        self.shape.distance_estimator(&Position::new(
+            wrap_finite(point.x(), self.spacing[0], self.copies[0]),
+            wrap_finite(point.y(), self.spacing[1], self.copies[1]),
+            wrap_finite(point.z(), self.spacing[2], self.copies[2]),
+        ))
+    }
+}
+
+/// The shape twisted around the z axis, turning by rate radians per unit up
+/// the axis. radius is how far the shape reaches from the axis, which limits
+/// how much the twist can stretch distances.
+pub struct Twist<T> {
+    shape: T,
+    rate: f64,
+    radius: f64,
+}
+
+impl<T: Marcher> Twist<T> {
+    pub fn new(shape: T, rate: f64, radius: f64) -> Twist<T> {
+        Twist {
+            shape,
+            rate,
+            radius,
+        }
+    }
+}
+
+impl<T: Marcher> Marcher for Twist<T> {
+    fn distance_estimator(&self, point: &Position) -> f64 {
+        let (sin, cos) = (-self.rate * point.z()).sin_cos();
+        let untwisted = Position::new(
+            cos * point.x() - sin * point.y(),
+            sin * point.x() + cos * point.y(),
+            point.z(),
+        );
+        // untwisting shears distances by rate times the distance from the
+        // axis, which is worst far from it. Every point between here and the
+        // shape is no further out than the furthest of the two. A shear of s
+        // stretches distances by at most its largest singular value.
+        let axis_distance = point.x().hypot(point.y()).max(self.radius);
+        let shear = self.rate * axis_distance;
+        let stretch = (shear + (shear * shear + 4.0).sqrt()) / 2.0;
+        self.shape.distance_estimator(&untwisted) / stretch
+    }
+}
+
+/// The shape bent in the xz plane, so the x axis curves into a circle of
+/// radius 1 / curvature. A positive curvature bends the ends of the x axis up
+/// towards the centre of the circle. extent is how far the shape reaches from
+/// the x axis towards the centre, which must be less than the radius.
+pub struct Bend<T> {
+    shape: T,
+    curvature: f64,
+    extent: f64,
+}
+
+impl<T: Marcher> Bend<T> {
+    pub fn new(shape: T, curvature: f64, extent: f64) -> Bend<T> {
+        assert!(curvature.abs() * extent < 1.0);
+        Bend {
+            shape,
+            curvature,
+            extent,
+        }
+    }
+}
+
+impl<T: Marcher> Marcher for Bend<T> {
+    fn distance_estimator(&self, point: &Position) -> f64 {
+        if self.curvature == 0.0 {
+            return self.shape.distance_estimator(point);
+        }
+        // work as if the bend is upwards, and flip the result back.
+        let side = self.curvature.signum();
+        let radius = 1.0 / self.curvature.abs();
+        let (x, z) = (point.x(), radius - side * point.z());
+        let distance = x.hypot(z);
+        let unbent = Position::new(radius * x.atan2(z), point.y(), side * (radius - distance));
+        // distances along the x axis are squashed by the bend closer to the
+        // centre than the radius. Every point between here and the shape is
+        // at least as far from the centre as the closer of the two.
+        let squash = (distance.min(radius - self.extent) / radius).min(1.0);
+        // that squashing goes to nothing at the centre, but the shape is
+        // known to be no closer to it than radius - extent.
+        let gap = radius - self.extent - distance;
+        (self.shape.distance_estimator(&unbent) * squash).max(gap)
+    }
+}
+
+/// The part of the shape in front of a plane through the origin, reflected
+/// to make the part behind it. Mirrors can be nested to give the shape more
+/// symmetry.
+pub struct Mirror<T> {
+    shape: T,
+    normal: Direction,
+}
+
+impl<T: Marcher> Mirror<T> {
+    /// the front of the plane faces along normal.
+    pub fn new(shape: T, normal: Direction) -> Mirror<T> {
+        Mirror {
+            shape,
+            normal: normal.normalise(),
+        }
+    }
+}
+
+impl<T: Marcher> Marcher for Mirror<T> {
+    fn distance_estimator(&self, point: &Position) -> f64 {
+        let height = self.normal.dot_position(point);
+        if height >= 0.0 {
+            self.shape.distance_estimator(point)
+        } else {
+            self.shape
+                .distance_estimator(&point.move_along(&self.normal, -2.0 * height))
+        }
+    }
+}
+
+/// The shape moved by an affine transform. Transforms which scale or skew
+/// the shape change distances, so the distance estimate is shrunk by the
+/// most the transform can squash a distance to keep it a bound.
+pub struct Affine<T> {
+    shape: T,
+    inv_transform: Matrix4<f64>,
+    /// the smallest amount the transform scales any direction by.
+    min_scale: f64,
+}
+
+impl<T: Marcher> Affine<T> {
+    pub fn new(shape: T, transform: Matrix4<f64>) -> Affine<T> {
+        assert!(transform.is_invertible());
+        let linear: Matrix3<f64> = transform
+            .fixed_slice::<nalgebra::U3, nalgebra::U3>(0, 0)
+            .into();
+        Affine {
+            shape,
+            inv_transform: transform.try_inverse().unwrap(),
+            min_scale: linear.singular_values().min(),
+        }
+    }
+}
+
+impl<T: Marcher> Marcher for Affine<T> {
+    fn distance_estimator(&self, point: &Position) -> f64 {
+        self.shape
+            .distance_estimator(&point.affine_trans(&self.inv_transform))
+            * self.min_scale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::march::cuboid::Cuboid;
+    use crate::shape::march::sphere::Sphere;
+    use crate::shape::march::tests::assert_distance_bound;
+    use nalgebra::base::Vector3;
+
+    #[test]
+    fn repeats_are_bounds() {
+        assert_distance_bound(&Repeat::new(Sphere::new(0.3), [0.8, 0.0, 1.0]), 1.5, 0.05);
+        assert_distance_bound(
+            &RepeatFinite::new(Cuboid::new(0.4, 0.3, 0.5), [0.7, 0.9, 0.0], [3, 2, 1]),
+            1.5,
+            0.05,
+        );
+    }
+
+    #[test]
+    fn bend_is_a_bound() {
+        let bar = Cuboid::new(2.6, 0.4, 0.3);
+        assert_distance_bound(&Bend::new(bar, 0.6, 0.3), 1.5, 0.05);
+        let bar = Cuboid::new(2.6, 0.4, 0.3);
+        assert_distance_bound(&Bend::new(bar, -0.6, 0.3), 1.5, 0.05);
+    }
+
+    #[test]
+    fn mirror_and_affine_are_bounds() {
+        let sphere = Affine::new(
+            Sphere::new(0.5),
+            Matrix4::new_translation(&Vector3::new(0.6, 0.3, 0.0)),
+        );
+        assert_distance_bound(
+            &Mirror::new(sphere, Direction::new(1.0, 1.0, 0.0)),
+            1.5,
+            0.05,
+        );
+        // a squashed and skewed cube.
+        let mut transform = Matrix4::new_nonuniform_scaling(&Vector3::new(1.5, 0.5, 1.0));
+        transform[(0, 1)] = 0.4;
+        assert_distance_bound(
+            &Affine::new(Cuboid::new(1.0, 1.0, 1.0), transform),
+            1.5,
+            0.05,
+        );
+    }
+
+    #[test]
+    fn twist_is_a_bound() {
+        // a thin slab tilted against the twist is sheared the most.
+        let slab = Affine::new(
+            Cuboid::new(3.0, 0.2, 3.0),
+            Matrix4::new_rotation(Vector3::new(-0.6, 0.0, 0.0)),
+        );
+        assert_distance_bound(&Twist::new(slab, 1.0, 0.3), 1.5, 0.05);
+    }
+}
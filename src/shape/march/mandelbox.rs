@@ -0,0 +1,81 @@
+use super::Marcher;
+use crate::shape::{Direction, Position};
+
+/// The Mandelbox, made by repeatedly folding space inside a box and a sphere
+/// then scaling it. A scale of 2 gives a box about 12 across, and negative
+/// scales such as -1.5 give rounder, more intricate shapes.
+pub struct Mandelbox {
+    scale: f64,
+    fold_limit: f64,
+    min_radius_sq: f64,
+    fixed_radius_sq: f64,
+    iterations: u32,
+}
+
+impl Mandelbox {
+    /// points are reflected back inside a box fold_limit from the origin
+    /// along each axis, then inverted in a sphere of radius fixed_radius.
+    /// Points closer than min_radius are scaled up by a constant instead.
+    /// The usual values are 1, 0.5 and 1.
+    pub fn new(
+        scale: f64,
+        fold_limit: f64,
+        min_radius: f64,
+        fixed_radius: f64,
+        iterations: u32,
+    ) -> Mandelbox {
+        assert!(0.0 < min_radius && min_radius <= fixed_radius);
+        Mandelbox {
+            scale,
+            fold_limit,
+            min_radius_sq: min_radius * min_radius,
+            fixed_radius_sq: fixed_radius * fixed_radius,
+            iterations,
+        }
+    }
+}
+
+impl Marcher for Mandelbox {
+    fn distance_estimator(&self, point: &Position) -> f64 {
+        let fold = |x: f64| 2.0 * x.clamp(-self.fold_limit, self.fold_limit) - x;
+        let mut z = point.to_direction();
+        // how much the iterations have stretched distances.
+        let mut derivative = 1.0;
+        for _ in 0..self.iterations {
+            z = Direction::new(fold(z.x()), fold(z.y()), fold(z.z()));
+
+            let radius_sq = z.len_sq();
+            let inversion = if radius_sq < self.min_radius_sq {
+                self.fixed_radius_sq / self.min_radius_sq
+            } else if radius_sq < self.fixed_radius_sq {
+                self.fixed_radius_sq / radius_sq
+            } else {
+                1.0
+            };
+
+            let scale = self.scale * inversion;
+            z = Direction::new(
+                z.x() * scale + point.x(),
+                z.y() * scale + point.y(),
+                z.z() * scale + point.z(),
+            );
+            derivative = derivative * scale.abs() + 1.0;
+        }
+        z.len() / derivative
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::march::combine::Round;
+    use crate::shape::march::tests::assert_distance_bound;
+
+    #[test]
+    fn estimate_is_a_bound() {
+        // the estimate is never negative, so grow the shape a little to give
+        // it an inside.
+        let mandelbox = Round::new(Mandelbox::new(-1.5, 1.0, 0.5, 1.0, 10), 0.05);
+        assert_distance_bound(&mandelbox, 5.0, 0.15);
+    }
+}
@@ -0,0 +1,62 @@
+use super::Marcher;
+use crate::shape::{Direction, Position};
+
+// points which escape further than this from the origin are outside.
+const BAILOUT: f64 = 2.0;
+
+/// The Mandelbulb, a 3D analogue of the Mandelbrot set made by raising points
+/// to a power in spherical coordinates. Power 8 gives the classic bulb. It
+/// fits within a radius of about 1.2.
+pub struct Mandelbulb {
+    power: f64,
+    iterations: u32,
+}
+
+impl Mandelbulb {
+    /// more iterations give finer detail, but take longer.
+    pub fn new(power: f64, iterations: u32) -> Mandelbulb {
+        Mandelbulb { power, iterations }
+    }
+}
+
+impl Marcher for Mandelbulb {
+    fn distance_estimator(&self, point: &Position) -> f64 {
+        let mut z = point.to_direction();
+        // the derivative of the distance from the origin as the point moves.
+        let mut derivative = 1.0;
+        let mut radius = z.len();
+        for _ in 0..self.iterations {
+            if radius > BAILOUT {
+                break;
+            }
+            let theta = if radius == 0.0 {
+                0.0
+            } else {
+                (z.z() / radius).acos() * self.power
+            };
+            let phi = z.y().atan2(z.x()) * self.power;
+            derivative = radius.powf(self.power - 1.0) * self.power * derivative + 1.0;
+            let scale = radius.powf(self.power);
+            z = Direction::new(
+                scale * theta.sin() * phi.cos() + point.x(),
+                scale * theta.sin() * phi.sin() + point.y(),
+                scale * theta.cos() + point.z(),
+            );
+            radius = z.len();
+        }
+        // the origin never moves, and its logarithm would give NaN.
+        let radius = radius.max(f64::MIN_POSITIVE);
+        0.5 * radius.ln() * radius / derivative
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::march::tests::assert_distance_bound;
+
+    #[test]
+    fn estimate_is_a_bound() {
+        assert_distance_bound(&Mandelbulb::new(8.0, 8), 1.5, 0.05);
+    }
+}
@@ -0,0 +1,62 @@
+use super::Marcher;
+use crate::shape::Position;
+
+/// The Menger sponge, a cube with a cross shaped hole through the middle of
+/// each face, with each of the remaining smaller cubes cut the same way again.
+pub struct Menger {
+    half_size: f64,
+    iterations: u32,
+}
+
+impl Menger {
+    /// size is the length of each side, and iterations the number of times
+    /// holes are cut.
+    pub fn new(size: f64, iterations: u32) -> Menger {
+        Menger {
+            half_size: size / 2.0,
+            iterations,
+        }
+    }
+}
+
+impl Marcher for Menger {
+    fn distance_estimator(&self, point: &Position) -> f64 {
+        // work on a cube from -1 to 1.
+        let (x, y, z) = (
+            point.x() / self.half_size,
+            point.y() / self.half_size,
+            point.z() / self.half_size,
+        );
+        let outside = (x.abs() - 1.0).max(0.0).hypot((y.abs() - 1.0).max(0.0));
+        let outside = outside.hypot((z.abs() - 1.0).max(0.0));
+        let mut distance = outside
+            + (x.abs() - 1.0)
+                .max(y.abs() - 1.0)
+                .max(z.abs() - 1.0)
+                .min(0.0);
+
+        let mut scale = 1.0;
+        for _ in 0..self.iterations {
+            // the position within the smaller cube this point is in, and how
+            // far it is from the middle third along each axis.
+            let cell = |c: f64| (1.0 - 3.0 * ((c * scale).rem_euclid(2.0) - 1.0).abs()).abs();
+            let (a, b, c) = (cell(x), cell(y), cell(z));
+            scale *= 3.0;
+            // the hole is the union of three square bars along each axis.
+            let hole = (a.max(b).min(b.max(c)).min(c.max(a)) - 1.0) / scale;
+            distance = distance.max(hole);
+        }
+        distance * self.half_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::march::tests::assert_distance_bound;
+
+    #[test]
+    fn estimate_is_a_bound() {
+        assert_distance_bound(&Menger::new(2.0, 3), 1.5, 0.05);
+    }
+}
@@ -0,0 +1,71 @@
+use super::Marcher;
+use crate::shape::Position;
+
+/// The Sierpinski tetrahedron, a tetrahedron made of four half sized copies of
+/// itself at its corners.
+pub struct Sierpinski {
+    half_size: f64,
+    iterations: u32,
+}
+
+impl Sierpinski {
+    /// size is the length of the side of the cube the tetrahedron's corners
+    /// sit on, and iterations the number of times it is divided.
+    pub fn new(size: f64, iterations: u32) -> Sierpinski {
+        Sierpinski {
+            half_size: size / 2.0,
+            iterations,
+        }
+    }
+}
+
+impl Marcher for Sierpinski {
+    fn distance_estimator(&self, point: &Position) -> f64 {
+        // the corners are at (1, 1, 1), (-1, -1, 1), (1, -1, -1) and
+        // (-1, 1, -1).
+        let (mut x, mut y, mut z) = (
+            point.x() / self.half_size,
+            point.y() / self.half_size,
+            point.z() / self.half_size,
+        );
+        for _ in 0..self.iterations {
+            // reflect the point into the copy in the (1, 1, 1) corner, then
+            // scale that copy up to the full size.
+            if x + y < 0.0 {
+                let swap = x;
+                x = -y;
+                y = -swap;
+            }
+            if x + z < 0.0 {
+                let swap = x;
+                x = -z;
+                z = -swap;
+            }
+            if y + z < 0.0 {
+                let swap = y;
+                y = -z;
+                z = -swap;
+            }
+            x = 2.0 * x - 1.0;
+            y = 2.0 * y - 1.0;
+            z = 2.0 * z - 1.0;
+        }
+
+        // the distance to the faces of the tetrahedron, which face away from
+        // the corner opposite them.
+        let tetrahedron = (-x - y - z).max(x + y - z).max(-x + y + z).max(x - y + z) - 1.0;
+        let distance = tetrahedron / 3f64.sqrt();
+        distance * 0.5f64.powi(self.iterations as i32) * self.half_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::march::tests::assert_distance_bound;
+
+    #[test]
+    fn estimate_is_a_bound() {
+        assert_distance_bound(&Sierpinski::new(2.0, 4), 1.5, 0.05);
+    }
+}
@@ -16,7 +16,7 @@ impl Marcher for Sphere {
         point.len() - self.radius
     }
 
-    fn get_normal(&self, point: &Position) -> Direction {
+    fn get_normal(&self, point: &Position, _epsilon: f64) -> Direction {
         point.to_direction().normalise()
     }
 }
@@ -0,0 +1,223 @@
+use crate::ray::GeometricRay;
+use crate::shape::polynomial;
+use crate::shape::{Bounds, Collision, Direction, Position, Shape};
+
+/// One of the centres of a Metaballs shape.
+#[derive(Debug, Clone)]
+pub struct Ball {
+    pub centre: Position,
+    /// the distance at which the ball stops having any effect.
+    pub radius: f64,
+    /// the strength of the ball at its centre. Negative weights carve hollows
+    /// out of the balls around them.
+    pub weight: f64,
+}
+
+impl Ball {
+    /// the polynomial in t giving the strength of the ball along the ray,
+    /// which is only meaningful where the ray is within the radius.
+    fn strength_along(&self, origin: &Position, direction: &Direction) -> Vec<f64> {
+        // the strength falls off as (1 - r^2 / R^2)^3, which fades smoothly
+        // to 0 at the radius and is a polynomial along a ray.
+        let offset = Direction::from_two_points(&self.centre, origin);
+        let radius2 = self.radius * self.radius;
+        let falloff = [
+            -direction.len_sq() / radius2,
+            -2.0 * direction.dot(&offset) / radius2,
+            1.0 - offset.len_sq() / radius2,
+        ];
+        let cube = polynomial::multiply(&polynomial::multiply(&falloff, &falloff), &falloff);
+        cube.iter()
+            .map(|coefficient| coefficient * self.weight)
+            .collect()
+    }
+
+    /// the part of the ray within the radius if any, and the strongest the
+    /// ball gets along the ray.
+    fn span(&self, ray: &GeometricRay) -> Option<(f64, f64, f64)> {
+        let (origin, direction) = (ray.origin(), ray.direction());
+        let offset = Direction::from_two_points(&self.centre, origin);
+        match polynomial::quadratic(
+            direction.len_sq(),
+            2.0 * direction.dot(&offset),
+            offset.len_sq() - self.radius * self.radius,
+        )[..]
+        {
+            [enter, exit] if exit > 0.0 && enter < exit => {
+                // the ray is closest to the centre half way through.
+                let half_chord2 = direction.len_sq() * (exit - enter).powi(2) / 4.0;
+                let peak = self.weight * (half_chord2 / (self.radius * self.radius)).powi(3);
+                Some((enter.max(0.0), exit, peak))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A blobby surface around a set of balls, which merge smoothly into each
+/// other like drops of liquid as they get closer. Each ball gives a strength
+/// which falls off to nothing at its radius, and the shape is everywhere the
+/// total strength is above the threshold.
+///
+/// The strength along a ray is a polynomial between the points where it
+/// enters or leaves a ball, so the surface is found exactly by solving it.
+#[derive(Debug)]
+pub struct Metaballs {
+    balls: Vec<Ball>,
+    threshold: f64,
+}
+
+impl Metaballs {
+    /// the threshold must be positive. A single ball on its own has a radius
+    /// of R sqrt(1 - (threshold / weight)^(1/3)).
+    pub fn new(balls: Vec<Ball>, threshold: f64) -> Metaballs {
+        assert!(threshold > 0.0);
+        Metaballs { balls, threshold }
+    }
+
+    /// the first point the total strength crosses the threshold, rising when
+    /// entering the shape or falling when leaving it.
+    fn crossing(&self, ray: &GeometricRay, entering: bool) -> Option<Collision> {
+        let spans: Vec<(&Ball, (f64, f64, f64))> = self
+            .balls
+            .iter()
+            .filter_map(|ball| ball.span(ray).map(|span| (ball, span)))
+            .collect();
+
+        // the strength is a single polynomial between each point where the
+        // ray enters or leaves a ball.
+        let mut edges: Vec<f64> = spans
+            .iter()
+            .flat_map(|(_, (enter, exit, _))| vec![*enter, *exit])
+            .collect();
+        edges.sort_by(|a, b| a.total_cmp(b));
+        edges.dedup();
+
+        let (origin, direction) = (ray.origin(), ray.direction());
+        edges.windows(2).find_map(|edge| {
+            let (start, end) = (edge[0], edge[1]);
+            let inside: Vec<&(&Ball, (f64, f64, f64))> = spans
+                .iter()
+                .filter(|(_, (enter, exit, _))| *enter <= start && end <= *exit)
+                .collect();
+            // skip pieces where the balls can't add up to the threshold,
+            // which are common as the balls reach well past the surface.
+            let peak: f64 = inside.iter().map(|(_, (_, _, peak))| peak.max(0.0)).sum();
+            if peak < self.threshold {
+                return None;
+            }
+
+            // measure from the start of each piece, which keeps the
+            // polynomials well conditioned far along the ray.
+            let piece_origin = origin.move_along(direction, start);
+            let mut strength = vec![0.0; 7];
+            inside.iter().for_each(|(ball, _)| {
+                let ball_strength = ball.strength_along(&piece_origin, direction);
+                strength
+                    .iter_mut()
+                    .zip(ball_strength)
+                    .for_each(|(total, coefficient)| *total += coefficient);
+            });
+            strength[6] -= self.threshold;
+
+            let slope = polynomial::derivative(&strength);
+            polynomial::roots_between(&strength, 0.0, end - start)
+                .into_iter()
+                .find(|t| (polynomial::evaluate(&slope, *t) > 0.0) == entering)
+                .map(|t| {
+                    let position = piece_origin.move_along(direction, t);
+                    Collision::new(start + t, self.normal(&position), position)
+                })
+        })
+    }
+
+    /// the strength is highest inside, so the normal faces down its gradient.
+    fn normal(&self, point: &Position) -> Direction {
+        let gradient = self
+            .balls
+            .iter()
+            .fold(Direction::new(0.0, 0.0, 0.0), |total, ball| {
+                let offset = Direction::from_two_points(&ball.centre, point);
+                let radius2 = ball.radius * ball.radius;
+                let falloff = 1.0 - offset.len_sq() / radius2;
+                if falloff <= 0.0 {
+                    return total;
+                }
+                let scale = ball.weight * 3.0 * falloff * falloff * 2.0 / radius2;
+                Direction::new(
+                    total.x() + offset.x() * scale,
+                    total.y() + offset.y() * scale,
+                    total.z() + offset.z() * scale,
+                )
+            });
+        gradient.normalise()
+    }
+}
+
+impl Shape for Metaballs {
+    fn collision(&self, ray: &GeometricRay) -> Option<Collision> {
+        self.crossing(ray, true)
+    }
+
+    fn collision_in(&self, ray: &GeometricRay) -> Option<Collision> {
+        self.crossing(ray, false)
+    }
+
+    /// only balls with positive weights can raise the strength above the
+    /// threshold, so the shape is within them.
+    fn bounds(&self) -> Option<Bounds> {
+        Bounds::enclosing(
+            self.balls
+                .iter()
+                .filter(|ball| ball.weight > 0.0)
+                .map(|ball| {
+                    let corner = Direction::new(ball.radius, ball.radius, ball.radius);
+                    Bounds::new(
+                        ball.centre.move_along(&corner, -1.0),
+                        ball.centre.move_along(&corner, 1.0),
+                    )
+                }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::tests::assert_hit;
+
+    fn ball(x: f64) -> Ball {
+        Ball {
+            centre: Position::new(x, 0.0, 0.0),
+            radius: 2.0,
+            weight: 1.0,
+        }
+    }
+
+    #[test]
+    fn single_ball_matches_radius() {
+        let metaballs = Metaballs::new(vec![ball(0.0)], 0.5);
+        let radius = 2.0 * f64::sqrt(1.0 - 0.5f64.cbrt());
+        let ray = GeometricRay::new(Position::new(0.0, -5.0, 0.0), Direction::FORWARDS, 0.0);
+        assert_hit(
+            metaballs.collision(&ray),
+            5.0 - radius,
+            &Direction::BACKWARDS,
+        );
+    }
+
+    #[test]
+    fn pair_matches_closed_form() {
+        // half way between the balls each gives half the threshold, so the
+        // surface is where (1 - r^2 / R^2)^3 = 1 / 2, r^2 = 0.5^2 + y^2.
+        let metaballs = Metaballs::new(vec![ball(-0.5), ball(0.5)], 1.0);
+        let r2 = 4.0 * (1.0 - 0.5f64.cbrt());
+        let y = f64::sqrt(r2 - 0.25);
+
+        let ray = GeometricRay::new(Position::new(0.0, -5.0, 0.0), Direction::FORWARDS, 0.0);
+        assert_hit(metaballs.collision(&ray), 5.0 - y, &Direction::BACKWARDS);
+        let inside = GeometricRay::new(Position::ORIGIN, Direction::FORWARDS, 0.0);
+        assert!(metaballs.collision(&inside).is_none());
+        assert_hit(metaballs.collision_in(&inside), y, &Direction::FORWARDS);
+    }
+}
@@ -0,0 +1,51 @@
+use crate::animation::Track;
+use crate::ray::GeometricRay;
+use crate::shape::{Bounds, Collision, Shape};
+use nalgebra::base::Matrix4;
+
+/// A shape with an affine transform which changes during the exposure,
+/// following a track of transforms. Gives motion blur when the shutter is
+/// open for some time. The shape disappears at any time where the transform
+/// cannot be inverted.
+pub struct MovingAffine<S> {
+    shape: S,
+    transform: Track<Matrix4<f64>>,
+}
+
+impl<S: Shape> MovingAffine<S> {
+    pub fn new(shape: S, transform: Track<Matrix4<f64>>) -> MovingAffine<S> {
+        MovingAffine { shape, transform }
+    }
+}
+
+impl<S: Shape> Shape for MovingAffine<S> {
+    fn collision(&self, ray: &GeometricRay) -> Option<Collision> {
+        let transform = self.transform.at(ray.time());
+        let inv_transform = transform.try_inverse()?;
+        let new_ray = ray.affine_trans(&inv_transform);
+        self.shape
+            .collision(&new_ray)
+            .map(|collision| collision.affine_trans(&transform))
+    }
+
+    fn collision_in(&self, ray: &GeometricRay) -> Option<Collision> {
+        let transform = self.transform.at(ray.time());
+        let inv_transform = transform.try_inverse()?;
+        let new_ray = ray.affine_trans(&inv_transform);
+        self.shape
+            .collision_in(&new_ray)
+            .map(|collision| collision.affine_trans(&transform))
+    }
+
+    /// each point of the shape is a weighted sum of the transforms applied to
+    /// it, so it stays within the hull of the transformed bounds.
+    fn bounds(&self) -> Option<Bounds> {
+        let bounds = self.shape.bounds()?;
+        Bounds::from_points(self.transform.hull().iter().flat_map(|matrix| {
+            bounds
+                .corners()
+                .into_iter()
+                .map(move |corner| corner.affine_trans(matrix))
+        }))
+    }
+}
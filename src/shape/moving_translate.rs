@@ -0,0 +1,43 @@
+use crate::animation::Track;
+use crate::ray::GeometricRay;
+use crate::shape::{Bounds, Collision, Position, Shape};
+
+/// A shape which moves during the exposure, following a track of
+/// translations. Gives motion blur when the shutter is open for some time.
+pub struct MovingTranslate<T> {
+    translation: Track<Position>,
+    shape: T,
+}
+
+impl<T> MovingTranslate<T> {
+    pub fn new(translation: Track<Position>, shape: T) -> MovingTranslate<T> {
+        MovingTranslate { translation, shape }
+    }
+}
+
+impl<T: Shape> Shape for MovingTranslate<T> {
+    fn collision(&self, ray: &GeometricRay) -> Option<Collision> {
+        let translation = self.translation.at(ray.time());
+        let new_ray = ray.with_origin(ray.origin() - &translation);
+        self.shape
+            .collision(&new_ray)
+            .map(|collision| collision.translate(&translation))
+    }
+
+    fn collision_in(&self, ray: &GeometricRay) -> Option<Collision> {
+        let translation = self.translation.at(ray.time());
+        let new_ray = ray.with_origin(ray.origin() - &translation);
+        self.shape
+            .collision_in(&new_ray)
+            .map(|collision| collision.translate(&translation))
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        let bounds = self.shape.bounds()?;
+        Bounds::enclosing(
+            self.translation.hull().iter().map(|translation| {
+                Bounds::new(&bounds.min + translation, &bounds.max + translation)
+            }),
+        )
+    }
+}
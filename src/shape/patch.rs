@@ -0,0 +1,254 @@
+use crate::collision::Collision;
+use crate::ray::GeometricRay;
+use crate::shape::{Bounds, Direction, Position, Shape};
+use nalgebra::base::{Matrix3, Vector3};
+
+pub mod bezier;
+pub mod nurbs;
+
+// the number of cells along each side of a block of the tessellation which
+// share a bounding box.
+const BLOCK_SIZE: usize = 4;
+// newton iteration stops once the ray is this close to the surface.
+const NEWTON_EPSILON: f64 = 1e-10;
+const NEWTON_STEPS: u32 = 10;
+// how far outside the unit square newton iteration may land before it is
+// considered to have slipped off the edge of the patch.
+const EDGE_TOLERANCE: f64 = 1e-6;
+
+/// A curved surface, parameterised by u and v between 0 and 1.
+pub trait Surface {
+    /// the point on the surface at u, v, with the derivatives of the point
+    /// along u and along v.
+    fn evaluate(&self, u: f64, v: f64) -> (Position, Direction, Direction);
+}
+
+/// A square group of cells of the tessellation, with the bounds of its
+/// points.
+struct Block {
+    bounds: Bounds,
+    cells_u: (usize, usize),
+    cells_v: (usize, usize),
+}
+
+/// A shape made from a curved surface. Rays are intersected with a grid of
+/// triangles approximating the surface, then the hit is refined onto the
+/// surface itself with newton iteration, so silhouettes follow the
+/// tessellation but shading follows the true surface.
+///
+/// The front of the surface faces along the u derivative crossed with the v
+/// derivative. Rays hitting the front enter the shape and rays hitting the
+/// back leave it, so a closed set of consistently oriented patches joined
+/// with a Union behaves as a solid.
+pub struct Patch<T> {
+    surface: T,
+    subdivisions: usize,
+    /// the tessellated points, row by row along u.
+    points: Vec<Position>,
+    blocks: Vec<Block>,
+}
+
+impl<T: Surface> Patch<T> {
+    /// subdivisions is the number of cells along each side of the
+    /// tessellation.
+    pub fn new(surface: T, subdivisions: usize) -> Patch<T> {
+        assert!(subdivisions >= 1);
+        let points: Vec<Position> = (0..=subdivisions)
+            .flat_map(|v| (0..=subdivisions).map(move |u| (u, v)))
+            .map(|(u, v)| {
+                let (point, _, _) = surface.evaluate(
+                    u as f64 / subdivisions as f64,
+                    v as f64 / subdivisions as f64,
+                );
+                point
+            })
+            .collect();
+
+        let starts = (0..subdivisions).step_by(BLOCK_SIZE);
+        let blocks = starts
+            .clone()
+            .flat_map(|v| starts.clone().map(move |u| (u, v)))
+            .map(|(u, v)| {
+                let cells_u = (u, (u + BLOCK_SIZE).min(subdivisions));
+                let cells_v = (v, (v + BLOCK_SIZE).min(subdivisions));
+                let corners = (cells_v.0..=cells_v.1)
+                    .flat_map(|v| (cells_u.0..=cells_u.1).map(move |u| (u, v)))
+                    .map(|(u, v)| points[v * (subdivisions + 1) + u].clone());
+                Block {
+                    bounds: Bounds::from_points(corners).unwrap(),
+                    cells_u,
+                    cells_v,
+                }
+            })
+            .collect();
+
+        Patch {
+            surface,
+            subdivisions,
+            points,
+            blocks,
+        }
+    }
+
+    fn point(&self, u: usize, v: usize) -> &Position {
+        &self.points[v * (self.subdivisions + 1) + u]
+    }
+
+    /// the nearest hit on the tessellation, as a distance along the ray and
+    /// the u, v coordinates on the surface.
+    fn tessellation_collision(&self, ray: &GeometricRay) -> Option<(f64, f64, f64)> {
+        let cell_size = 1.0 / self.subdivisions as f64;
+        self.blocks
+            .iter()
+            .filter(|block| hits_bounds(ray, &block.bounds))
+            .flat_map(|block| {
+                (block.cells_v.0..block.cells_v.1)
+                    .flat_map(move |v| (block.cells_u.0..block.cells_u.1).map(move |u| (u, v)))
+            })
+            .flat_map(|(u, v)| {
+                let corner = self.point(u, v);
+                let right = self.point(u + 1, v);
+                let opposite = self.point(u + 1, v + 1);
+                let above = self.point(u, v + 1);
+                let (u, v) = (u as f64 * cell_size, v as f64 * cell_size);
+                // the barycentric coordinates of each triangle map back onto
+                // the cell.
+                let lower = triangle_collision(ray, corner, right, opposite)
+                    .map(|(t, a, b)| (t, u + (a + b) * cell_size, v + b * cell_size));
+                let upper = triangle_collision(ray, corner, opposite, above)
+                    .map(|(t, a, b)| (t, u + a * cell_size, v + (a + b) * cell_size));
+                lower.into_iter().chain(upper)
+            })
+            .min_by(|(t1, _, _), (t2, _, _)| t1.total_cmp(t2))
+    }
+
+    /// move a hit on the tessellation onto the surface. Returns None if the
+    /// iteration doesn't converge, or leaves the patch.
+    fn refine(
+        &self,
+        ray: &GeometricRay,
+        (mut t, mut u, mut v): (f64, f64, f64),
+    ) -> Option<(f64, f64, f64)> {
+        let (origin, direction) = (ray.origin(), ray.direction());
+        for _ in 0..NEWTON_STEPS {
+            let (point, du, dv) = self.surface.evaluate(u, v);
+            let target = origin.move_along(direction, t);
+            let error = Vector3::new(
+                point.x() - target.x(),
+                point.y() - target.y(),
+                point.z() - target.z(),
+            );
+            if error.norm() < NEWTON_EPSILON {
+                let inside = |x: f64| (-EDGE_TOLERANCE..=1.0 + EDGE_TOLERANCE).contains(&x);
+                return if t > 0.0 && inside(u) && inside(v) {
+                    Some((t, u, v))
+                } else {
+                    None
+                };
+            }
+            let jacobian = Matrix3::new(
+                du.x(),
+                dv.x(),
+                -direction.x(),
+                du.y(),
+                dv.y(),
+                -direction.y(),
+                du.z(),
+                dv.z(),
+                -direction.z(),
+            );
+            let step = jacobian.try_inverse()? * error;
+            u -= step.x;
+            v -= step.y;
+            t -= step.z;
+        }
+        None
+    }
+
+    /// the first hit on the surface, and whether it is on the front.
+    fn surface_collision(&self, ray: &GeometricRay) -> Option<(Collision, bool)> {
+        let hit = self.tessellation_collision(ray)?;
+        let (t, u, v) = self.refine(ray, hit).unwrap_or(hit);
+        let (u, v) = (u.clamp(0.0, 1.0), v.clamp(0.0, 1.0));
+
+        let (_, du, dv) = self.surface.evaluate(u, v);
+        let normal = du.cross(&dv).normalise();
+        let front = normal.dot(ray.direction()) < 0.0;
+        let position = ray.origin().move_along(ray.direction(), t);
+        Some((Collision::new(t, normal, position), front))
+    }
+}
+
+/// whether a ray passes through a box in front of its origin.
+fn hits_bounds(ray: &GeometricRay, bounds: &Bounds) -> bool {
+    let (origin, direction) = (ray.origin(), ray.direction());
+    let mut near: f64 = 0.0;
+    let mut far = f64::INFINITY;
+    for (start, speed, min, max) in &[
+        (origin.x(), direction.x(), bounds.min.x(), bounds.max.x()),
+        (origin.y(), direction.y(), bounds.min.y(), bounds.max.y()),
+        (origin.z(), direction.z(), bounds.min.z(), bounds.max.z()),
+    ] {
+        if *speed == 0.0 {
+            if start < min || start > max {
+                return false;
+            }
+            continue;
+        }
+        let (t1, t2) = ((min - start) / speed, (max - start) / speed);
+        near = near.max(t1.min(t2));
+        far = far.min(t1.max(t2));
+    }
+    near <= far
+}
+
+/// where a ray crosses a triangle, with the barycentric coordinates of the
+/// hit along the edges from a to b and from a to c.
+fn triangle_collision(
+    ray: &GeometricRay,
+    a: &Position,
+    b: &Position,
+    c: &Position,
+) -> Option<(f64, f64, f64)> {
+    // Möller-Trumbore intersection.
+    let (origin, direction) = (ray.origin(), ray.direction());
+    let edge1 = Direction::from_two_points(a, b);
+    let edge2 = Direction::from_two_points(a, c);
+    let p = direction.cross(&edge2);
+    let determinant = edge1.dot(&p);
+    if determinant == 0.0 {
+        return None;
+    }
+    let to_origin = Direction::from_two_points(a, origin);
+    let u = to_origin.dot(&p) / determinant;
+    let q = to_origin.cross(&edge1);
+    let v = direction.dot(&q) / determinant;
+    if u < 0.0 || v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = edge2.dot(&q) / determinant;
+    if t <= 0.0 {
+        return None;
+    }
+    Some((t, u, v))
+}
+
+impl<T: Surface> Shape for Patch<T> {
+    fn collision(&self, ray: &GeometricRay) -> Option<Collision> {
+        match self.surface_collision(ray) {
+            Some((collision, true)) => Some(collision),
+            _ => None,
+        }
+    }
+
+    fn collision_in(&self, ray: &GeometricRay) -> Option<Collision> {
+        match self.surface_collision(ray) {
+            Some((collision, false)) => Some(collision),
+            _ => None,
+        }
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Bounds::enclosing(self.blocks.iter().map(|block| block.bounds.clone()))
+    }
+}
@@ -0,0 +1,59 @@
+use super::Surface;
+use crate::shape::{Direction, Position};
+
+/// A bicubic Bézier patch. The surface passes through the four corner control
+/// points, and is pulled towards the others.
+pub struct Bezier {
+    /// control points row by row along u.
+    control_points: [[Position; 4]; 4],
+}
+
+impl Bezier {
+    pub fn new(control_points: [[Position; 4]; 4]) -> Bezier {
+        Bezier { control_points }
+    }
+}
+
+/// the cubic Bernstein polynomials at t, and their derivatives.
+fn bernstein(t: f64) -> ([f64; 4], [f64; 4]) {
+    let s = 1.0 - t;
+    (
+        [s * s * s, 3.0 * t * s * s, 3.0 * t * t * s, t * t * t],
+        [
+            -3.0 * s * s,
+            3.0 * s * s - 6.0 * t * s,
+            6.0 * t * s - 3.0 * t * t,
+            3.0 * t * t,
+        ],
+    )
+}
+
+impl Surface for Bezier {
+    fn evaluate(&self, u: f64, v: f64) -> (Position, Direction, Direction) {
+        let (bu, dbu) = bernstein(u);
+        let (bv, dbv) = bernstein(v);
+
+        let mut sums = [[0.0; 3]; 3];
+        for (row, points) in self.control_points.iter().enumerate() {
+            for (column, point) in points.iter().enumerate() {
+                let weights = [
+                    bu[column] * bv[row],
+                    dbu[column] * bv[row],
+                    bu[column] * dbv[row],
+                ];
+                for (sum, weight) in sums.iter_mut().zip(&weights) {
+                    sum[0] += weight * point.x();
+                    sum[1] += weight * point.y();
+                    sum[2] += weight * point.z();
+                }
+            }
+        }
+
+        let [point, du, dv] = sums;
+        (
+            Position::new(point[0], point[1], point[2]),
+            Direction::new(du[0], du[1], du[2]),
+            Direction::new(dv[0], dv[1], dv[2]),
+        )
+    }
+}
@@ -0,0 +1,156 @@
+use super::Surface;
+use crate::shape::{Direction, Position};
+
+/// A non-uniform rational B-spline surface. Unlike a Bézier patch it can have
+/// any number of control points, each with its own weight, and can represent
+/// conic sections such as circles exactly.
+pub struct Nurbs {
+    /// control points and their weights, row by row along u.
+    control_points: Vec<Vec<(Position, f64)>>,
+    u_knots: Vec<f64>,
+    v_knots: Vec<f64>,
+    u_degree: usize,
+    v_degree: usize,
+}
+
+impl Nurbs {
+    /// each row of control points runs along u, and the rows are spread out
+    /// along v. There must be as many knots in each direction as control
+    /// points plus the degree plus 1. The surface covers the range of knots
+    /// from the degree'th knot to the degree'th from last.
+    pub fn new(
+        control_points: Vec<Vec<(Position, f64)>>,
+        u_knots: Vec<f64>,
+        v_knots: Vec<f64>,
+        u_degree: usize,
+        v_degree: usize,
+    ) -> Nurbs {
+        assert!(!control_points.is_empty());
+        assert_eq!(u_knots.len(), control_points[0].len() + u_degree + 1);
+        assert_eq!(v_knots.len(), control_points.len() + v_degree + 1);
+        Nurbs {
+            control_points,
+            u_knots,
+            v_knots,
+            u_degree,
+            v_degree,
+        }
+    }
+
+    /// knots for a surface which starts and ends at its first and last
+    /// control points, with evenly spaced knots in between.
+    pub fn clamped_knots(control_points: usize, degree: usize) -> Vec<f64> {
+        let spans = control_points - degree;
+        (0..control_points + degree + 1)
+            .map(|index| (index.saturating_sub(degree)).min(spans) as f64 / spans as f64)
+            .collect()
+    }
+}
+
+/// the value of every basis function of the given degree at t, along with
+/// their derivatives, using the Cox-de Boor recursion.
+fn basis(knots: &[f64], degree: usize, t: f64) -> (Vec<f64>, Vec<f64>) {
+    let count = knots.len() - degree - 1;
+    // map the unit interval onto the valid range of knots. The last knot
+    // span is closed at the end, so the end of the surface is included.
+    let (start, end) = (knots[degree], knots[count]);
+    let t = start + t * (end - start);
+    let last_span = (0..knots.len() - 1)
+        .rev()
+        .find(|&index| knots[index] < knots[index + 1])
+        .unwrap();
+    let mut values: Vec<f64> = (0..knots.len() - 1)
+        .map(|index| {
+            let inside = knots[index] <= t && t < knots[index + 1];
+            if inside || (index == last_span && t >= knots[index + 1]) {
+                1.0
+            } else {
+                0.0
+            }
+        })
+        .collect();
+
+    // 0 / 0 is taken to be 0.
+    let ratio = |numerator: f64, denominator: f64| {
+        if denominator == 0.0 {
+            0.0
+        } else {
+            numerator / denominator
+        }
+    };
+    let mut derivatives = vec![0.0; count];
+    for level in 1..=degree {
+        if level == degree {
+            derivatives = (0..count)
+                .map(|index| {
+                    level as f64
+                        * (ratio(values[index], knots[index + level] - knots[index])
+                            - ratio(
+                                values[index + 1],
+                                knots[index + level + 1] - knots[index + 1],
+                            ))
+                })
+                .collect();
+        }
+        values = (0..knots.len() - 1 - level)
+            .map(|index| {
+                ratio(t - knots[index], knots[index + level] - knots[index]) * values[index]
+                    + ratio(
+                        knots[index + level + 1] - t,
+                        knots[index + level + 1] - knots[index + 1],
+                    ) * values[index + 1]
+            })
+            .collect();
+    }
+    values.truncate(count);
+    // the parameter was stretched onto the range of knots.
+    let derivatives = derivatives
+        .into_iter()
+        .map(|derivative| derivative * (end - start))
+        .collect();
+    (values, derivatives)
+}
+
+impl Surface for Nurbs {
+    fn evaluate(&self, u: f64, v: f64) -> (Position, Direction, Direction) {
+        let (bu, dbu) = basis(&self.u_knots, self.u_degree, u);
+        let (bv, dbv) = basis(&self.v_knots, self.v_degree, v);
+
+        // the weighted sums of the points and of the weights, and their
+        // derivatives along u and v.
+        let mut sums = [[0.0; 4]; 3];
+        for (row, points) in self.control_points.iter().enumerate() {
+            for (column, (point, weight)) in points.iter().enumerate() {
+                let factors = [
+                    bu[column] * bv[row],
+                    dbu[column] * bv[row],
+                    bu[column] * dbv[row],
+                ];
+                for (sum, factor) in sums.iter_mut().zip(&factors) {
+                    let factor = factor * weight;
+                    sum[0] += factor * point.x();
+                    sum[1] += factor * point.y();
+                    sum[2] += factor * point.z();
+                    sum[3] += factor;
+                }
+            }
+        }
+
+        // the quotient rule.
+        let [point, du, dv] = sums;
+        let weight = point[3];
+        let position = [point[0] / weight, point[1] / weight, point[2] / weight];
+        let derivative = |sum: [f64; 4]| {
+            Direction::new(
+                (sum[0] - sum[3] * position[0]) / weight,
+                (sum[1] - sum[3] * position[1]) / weight,
+                (sum[2] - sum[3] * position[2]) / weight,
+            )
+        };
+        (
+            Position::new(position[0], position[1], position[2]),
+            derivative(du),
+            derivative(dv),
+        )
+    }
+}
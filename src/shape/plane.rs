@@ -1,34 +1,53 @@
-use crate::shape::{Collision, Direction, Position, Shape};
+use crate::ray::GeometricRay;
+use crate::shape::{Collision, Direction, Shape};
 
-// infinite plane
-pub struct Plane(());
+/// An infinite half-space. Points p with p.normal <= offset are inside, so the
+/// normal points out of the solid. A ground at z = 0 has a normal of
+/// Direction::UP and an offset of 0.
+pub struct Plane {
+    normal: Direction,
+    offset: f64,
+}
 
 impl Plane {
-    pub fn new() -> Plane {
-        Plane(())
+    pub fn new(normal: Direction, offset: f64) -> Plane {
+        let length = normal.len();
+        Plane {
+            normal: normal.normalise(),
+            offset: offset / length,
+        }
+    }
+
+    /// the distance of the origin of a ray above the plane, and the rate at
+    /// which the ray moves away from it.
+    fn height_and_speed(&self, ray: &GeometricRay) -> (f64, f64) {
+        (
+            self.normal.dot_position(ray.origin()) - self.offset,
+            self.normal.dot(ray.direction()),
+        )
     }
 }
 
 impl Shape for Plane {
-    fn collision(&self, origin: &Position, direction: &Direction) -> Option<Collision> {
-        if origin.z() <= 0.0 || direction.z() >= 0.0 {
+    fn collision(&self, ray: &GeometricRay) -> Option<Collision> {
+        let (height, speed) = self.height_and_speed(ray);
+        if height <= 0.0 || speed >= 0.0 {
             return None;
         }
-        let t = -origin.z() / direction.z();
-        assert!(t >= 0.0);
-        let collision = origin.move_along(direction, t);
+        let t = -height / speed;
+        let collision = ray.origin().move_along(ray.direction(), t);
 
-        Some(Collision::new(t, Direction::UP, collision))
+        Some(Collision::new(t, self.normal.clone(), collision))
     }
 
-    fn collision_in(&self, origin: &Position, direction: &Direction) -> Option<Collision> {
-        if origin.z() >= 0.0 || direction.z() <= 0.0 {
+    fn collision_in(&self, ray: &GeometricRay) -> Option<Collision> {
+        let (height, speed) = self.height_and_speed(ray);
+        if height >= 0.0 || speed <= 0.0 {
             return None;
         }
-        let t = -origin.z() / direction.z();
-        assert!(t >= 0.0);
-        let collision = origin.move_along(direction, t);
+        let t = -height / speed;
+        let collision = ray.origin().move_along(ray.direction(), t);
 
-        Some(Collision::new(t, Direction::UP, collision))
+        Some(Collision::new(t, self.normal.clone(), collision))
     }
 }
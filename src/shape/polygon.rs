@@ -0,0 +1,185 @@
+use crate::ray::GeometricRay;
+use crate::sampler;
+use crate::shape::{Bounds, Collision, Direction, Position, Shape, SurfaceSample};
+
+/// A flat polygon with no thickness. It can be hit from either side, with the
+/// normal facing the ray, but has no inside, so collision_in never hits.
+#[derive(Debug)]
+pub struct Polygon {
+    vertices: Vec<Position>,
+    /// the front faces the side the vertices go anticlockwise around.
+    normal: Direction,
+    /// two perpendicular directions in the plane of the polygon.
+    u_axis: Direction,
+    v_axis: Direction,
+    /// the vertices relative to the first, along u_axis and v_axis.
+    points: Vec<(f64, f64)>,
+    area: f64,
+}
+
+impl Polygon {
+    /// the vertices should all lie in the same plane, and the edges between
+    /// them shouldn't cross. There must be at least 3 vertices, enclosing some
+    /// area, and the first two must be different.
+    pub fn new(vertices: Vec<Position>) -> Polygon {
+        assert!(vertices.len() >= 3);
+        // Newell's method gives a normal with a length of twice the area,
+        // even for polygons which aren't convex.
+        let newell = vertices.iter().zip(vertices.iter().cycle().skip(1)).fold(
+            Direction::new(0.0, 0.0, 0.0),
+            |total, (a, b)| {
+                Direction::new(
+                    total.x() + (a.y() - b.y()) * (a.z() + b.z()),
+                    total.y() + (a.z() - b.z()) * (a.x() + b.x()),
+                    total.z() + (a.x() - b.x()) * (a.y() + b.y()),
+                )
+            },
+        );
+        // without any area there is nowhere to sample, and no normal.
+        assert!(newell.len() > 0.0);
+        let normal = newell.normalise();
+        let first_edge = Direction::from_two_points(&vertices[0], &vertices[1]);
+        assert!(first_edge.len() > 0.0);
+        let u_axis = first_edge.normalise();
+        let v_axis = normal.cross(&u_axis);
+
+        let points = vertices
+            .iter()
+            .map(|vertex| {
+                let offset = Direction::from_two_points(&vertices[0], vertex);
+                (offset.dot(&u_axis), offset.dot(&v_axis))
+            })
+            .collect();
+
+        Polygon {
+            area: newell.len() / 2.0,
+            vertices,
+            normal,
+            u_axis,
+            v_axis,
+            points,
+        }
+    }
+
+    /// whether a point in the plane of the polygon is inside it, using the
+    /// even-odd rule.
+    fn contains(&self, (u, v): (f64, f64)) -> bool {
+        self.points
+            .iter()
+            .zip(self.points.iter().cycle().skip(1))
+            .filter(|((u1, v1), (u2, v2))| {
+                // count the edges crossed by a line from the point along u.
+                (*v1 > v) != (*v2 > v) && u < u1 + (v - v1) * (u2 - u1) / (v2 - v1)
+            })
+            .count()
+            % 2
+            == 1
+    }
+}
+
+impl Shape for Polygon {
+    fn collision(&self, ray: &GeometricRay) -> Option<Collision> {
+        let (origin, direction) = (ray.origin(), ray.direction());
+        let speed = self.normal.dot(direction);
+        if speed == 0.0 {
+            return None;
+        }
+        let to_vertex = Direction::from_two_points(origin, &self.vertices[0]);
+        let t = self.normal.dot(&to_vertex) / speed;
+        if t <= 0.0 {
+            return None;
+        }
+
+        let collision = origin.move_along(direction, t);
+        let offset = Direction::from_two_points(&self.vertices[0], &collision);
+        if !self.contains((offset.dot(&self.u_axis), offset.dot(&self.v_axis))) {
+            return None;
+        }
+
+        let normal = if speed < 0.0 {
+            self.normal.clone()
+        } else {
+            self.normal.negate()
+        };
+        Some(Collision::new(t, normal, collision))
+    }
+
+    fn collision_in(&self, _ray: &GeometricRay) -> Option<Collision> {
+        None
+    }
+
+    fn sample_surface(&self) -> Option<SurfaceSample> {
+        // pick points in the rectangle around the polygon until one lands
+        // inside.
+        let (min_u, max_u, min_v, max_v) = self.points.iter().fold(
+            (
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+            ),
+            |(min_u, max_u, min_v, max_v), (u, v)| {
+                (min_u.min(*u), max_u.max(*u), min_v.min(*v), max_v.max(*v))
+            },
+        );
+        let (u, v) = loop {
+            let (a, b) = sampler::next_2d();
+            let point = (min_u + a * (max_u - min_u), min_v + b * (max_v - min_v));
+            if self.contains(point) {
+                break point;
+            }
+        };
+
+        // either side is chosen with equal chance, as collision gives the
+        // normal of whichever side is hit.
+        let normal = if sampler::next() < 0.5 {
+            self.normal.clone()
+        } else {
+            self.normal.negate()
+        };
+        Some(SurfaceSample {
+            position: self.vertices[0]
+                .move_along(&self.u_axis, u)
+                .move_along(&self.v_axis, v),
+            normal,
+            // the area of both sides.
+            pdf: 1.0 / (2.0 * self.area),
+        })
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Bounds::from_points(self.vertices.iter().cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic]
+    fn rejects_collinear_vertices() {
+        Polygon::new(vec![
+            Position::new(0.0, 0.0, 0.0),
+            Position::new(1.0, 0.0, 0.0),
+            Position::new(2.0, 0.0, 0.0),
+        ]);
+    }
+
+    #[test]
+    fn samples_lie_inside() {
+        let triangle = Polygon::new(vec![
+            Position::new(0.0, 0.0, 0.0),
+            Position::new(2.0, 0.0, 0.0),
+            Position::new(0.0, 2.0, 0.0),
+        ]);
+        for _ in 0..100 {
+            let sample = triangle.sample_surface().unwrap();
+            let position = &sample.position;
+            assert!(position.x() >= -1e-9 && position.y() >= -1e-9);
+            assert!(position.x() + position.y() <= 2.0 + 1e-9);
+            assert!((sample.pdf - 0.25).abs() < 1e-9);
+            assert!(sample.normal.z().abs() == 1.0);
+        }
+    }
+}
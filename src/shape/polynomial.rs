@@ -0,0 +1,162 @@
+/// the real roots of a x^2 + b x + c = 0, smallest first. A repeated root is
+/// returned twice. If a is 0 the single root of the linear equation is
+/// returned.
+pub fn quadratic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    if a == 0.0 {
+        if b == 0.0 {
+            return Vec::new();
+        }
+        return vec![-c / b];
+    }
+
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return Vec::new();
+    }
+    // avoid subtracting two nearly equal numbers, which loses precision when
+    // b is much larger than a or c.
+    let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+    let (root1, root2) = if q == 0.0 { (0.0, 0.0) } else { (q / a, c / q) };
+    if root1 < root2 {
+        vec![root1, root2]
+    } else {
+        vec![root2, root1]
+    }
+}
+
+/// the value of a polynomial at x. coefficients go from the highest power
+/// down to the constant.
+pub fn evaluate(coefficients: &[f64], x: f64) -> f64 {
+    coefficients
+        .iter()
+        .fold(0.0, |total, coefficient| total * x + coefficient)
+}
+
+/// the derivative of a polynomial. coefficients go from the highest power
+/// down to the constant.
+pub fn derivative(coefficients: &[f64]) -> Vec<f64> {
+    let degree = coefficients.len().saturating_sub(1);
+    coefficients[..degree]
+        .iter()
+        .enumerate()
+        .map(|(power, coefficient)| coefficient * (degree - power) as f64)
+        .collect()
+}
+
+/// the product of two polynomials, with coefficients from the highest power
+/// down to the constant.
+pub fn multiply(a: &[f64], b: &[f64]) -> Vec<f64> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut product = vec![0.0; a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            product[i + j] += x * y;
+        }
+    }
+    product
+}
+
+/// the real roots of a polynomial of any degree, smallest first. coefficients
+/// go from the highest power down to the constant. Roots where the
+/// polynomial touches 0 without crossing it may be missed.
+///
+/// Between two neighbouring roots of the derivative a polynomial only rises
+/// or only falls, so it crosses 0 at most once, and the crossing can be found
+/// reliably by bisection. This is slower than a closed form solution, but
+/// doesn't suffer from its loss of precision.
+pub fn roots(coefficients: &[f64]) -> Vec<f64> {
+    let start = coefficients
+        .iter()
+        .position(|coefficient| *coefficient != 0.0)
+        .unwrap_or(coefficients.len());
+    let coefficients = &coefficients[start..];
+    match *coefficients {
+        [] | [_] => return Vec::new(),
+        [a, b] => return vec![-b / a],
+        [a, b, c] => return quadratic(a, b, c),
+        _ => (),
+    }
+
+    // every root lies within this distance of 0.
+    let bound = 1.0
+        + coefficients[1..]
+            .iter()
+            .map(|coefficient| (coefficient / coefficients[0]).abs())
+            .fold(0.0, f64::max);
+    roots_between(coefficients, -bound, bound)
+}
+
+/// the real roots of a polynomial between low and high, smallest first. This
+/// is more precise than roots when only a small range is of interest.
+pub fn roots_between(coefficients: &[f64], low: f64, high: f64) -> Vec<f64> {
+    let mut edges = vec![low];
+    edges.extend(
+        roots(&derivative(coefficients))
+            .into_iter()
+            .filter(|x| low < *x && *x < high),
+    );
+    edges.push(high);
+    edges
+        .windows(2)
+        .filter_map(|edge| bisect(coefficients, edge[0], edge[1]))
+        .collect()
+}
+
+/// the root between low and high of a polynomial which only rises or only
+/// falls between them. A root exactly at low belongs to the interval below.
+fn bisect(coefficients: &[f64], mut low: f64, mut high: f64) -> Option<f64> {
+    let low_value = evaluate(coefficients, low);
+    let high_value = evaluate(coefficients, high);
+    if low_value == 0.0 || (low_value > 0.0) == (high_value > 0.0) && high_value != 0.0 {
+        return None;
+    }
+
+    let rising = high_value > low_value;
+    // each step halves the interval, so this is far more than enough steps
+    // to reach the precision of an f64.
+    for _ in 0..100 {
+        let middle = 0.5 * (low + high);
+        if (evaluate(coefficients, middle) < 0.0) == rising {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    Some(0.5 * (low + high))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_roots(coefficients: &[f64], expected: &[f64]) {
+        let found = roots(coefficients);
+        assert_eq!(found.len(), expected.len(), "found {:?}", found);
+        for (root, expected) in found.iter().zip(expected) {
+            assert!((root - expected).abs() < 1e-9, "found {:?}", found);
+        }
+    }
+
+    #[test]
+    fn finds_quartic_roots() {
+        // (x + 2)(x + 1)(x - 0.5)(x - 3)
+        let quartic = multiply(&multiply(&[1.0, 2.0], &[1.0, 1.0]), &[1.0, -0.5]);
+        assert_roots(&multiply(&quartic, &[1.0, -3.0]), &[-2.0, -1.0, 0.5, 3.0]);
+        // (x^2 - 4)(x^2 + 1) has only two real roots.
+        assert_roots(&[1.0, 0.0, -3.0, 0.0, -4.0], &[-2.0, 2.0]);
+        // x^4 + 1 has none.
+        assert_roots(&[1.0, 0.0, 0.0, 0.0, 1.0], &[]);
+        // roots close together, as when a ray grazes a torus.
+        let close = multiply(&[1.0, 0.0, -1.0], &[1.0, 0.0, -1.0001]);
+        assert_roots(&close, &[-1.0001f64.sqrt(), -1.0, 1.0, 1.0001f64.sqrt()]);
+    }
+
+    #[test]
+    fn solves_quadratics() {
+        assert_eq!(quadratic(1.0, -3.0, 2.0), vec![1.0, 2.0]);
+        assert_eq!(quadratic(1.0, 0.0, 1.0), Vec::<f64>::new());
+        assert_eq!(quadratic(0.0, 2.0, -1.0), vec![0.5]);
+    }
+}
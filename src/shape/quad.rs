@@ -0,0 +1,94 @@
+use crate::ray::GeometricRay;
+use crate::sampler;
+use crate::shape::{Bounds, Collision, Direction, Position, Shape, SurfaceSample};
+
+/// A flat parallelogram with no thickness, such as a wall or a rectangular
+/// area light. It can be hit from either side, with the normal facing the
+/// ray, but has no inside, so collision_in never hits.
+#[derive(Debug)]
+pub struct Quad {
+    corner: Position,
+    edge1: Direction,
+    edge2: Direction,
+    /// perpendicular to both edges, with a length of the area.
+    cross: Direction,
+}
+
+impl Quad {
+    /// the quad has corners at corner, corner + edge1, corner + edge2 and
+    /// corner + edge1 + edge2. The front faces along edge1 x edge2.
+    pub fn new(corner: Position, edge1: Direction, edge2: Direction) -> Quad {
+        Quad {
+            cross: edge1.cross(&edge2),
+            corner,
+            edge1,
+            edge2,
+        }
+    }
+
+    /// a rectangle centred on the origin, lying in the xy plane and facing
+    /// up.
+    pub fn rectangle(width: f64, height: f64) -> Quad {
+        Quad::new(
+            Position::new(-width / 2.0, -height / 2.0, 0.0),
+            Direction::new(width, 0.0, 0.0),
+            Direction::new(0.0, height, 0.0),
+        )
+    }
+
+    fn corners(&self) -> Vec<Position> {
+        let corner1 = self.corner.move_along(&self.edge1, 1.0);
+        let corner2 = self.corner.move_along(&self.edge2, 1.0);
+        let corner3 = corner1.move_along(&self.edge2, 1.0);
+        vec![self.corner.clone(), corner1, corner2, corner3]
+    }
+}
+
+impl Shape for Quad {
+    fn collision(&self, ray: &GeometricRay) -> Option<Collision> {
+        let (origin, direction) = (ray.origin(), ray.direction());
+        let speed = self.cross.dot(direction);
+        if speed == 0.0 {
+            return None;
+        }
+        let to_corner = Direction::from_two_points(origin, &self.corner);
+        let t = self.cross.dot(&to_corner) / speed;
+        if t <= 0.0 {
+            return None;
+        }
+
+        // the hit as a fraction of the way along each edge.
+        let collision = origin.move_along(direction, t);
+        let offset = Direction::from_two_points(&self.corner, &collision);
+        let area_sq = self.cross.len_sq();
+        let u = self.cross.dot(&offset.cross(&self.edge2)) / area_sq;
+        let v = self.cross.dot(&self.edge1.cross(&offset)) / area_sq;
+        if !(0.0..=1.0).contains(&u) || !(0.0..=1.0).contains(&v) {
+            return None;
+        }
+
+        let normal = self.cross.normalise();
+        let normal = if speed < 0.0 { normal } else { normal.negate() };
+        Some(Collision::new(t, normal, collision))
+    }
+
+    fn collision_in(&self, _ray: &GeometricRay) -> Option<Collision> {
+        None
+    }
+
+    fn sample_surface(&self) -> Option<SurfaceSample> {
+        let (u, v) = sampler::next_2d();
+        Some(SurfaceSample {
+            position: self
+                .corner
+                .move_along(&self.edge1, u)
+                .move_along(&self.edge2, v),
+            normal: self.cross.normalise(),
+            pdf: 1.0 / self.cross.len(),
+        })
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Bounds::from_points(self.corners())
+    }
+}
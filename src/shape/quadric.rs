@@ -0,0 +1,162 @@
+use crate::ray::GeometricRay;
+use crate::shape::convex::Span;
+use crate::shape::{Bounds, Collision, Direction, Position, Shape};
+use nalgebra::base::{Matrix3, Matrix4, Vector3, Vector4};
+
+/// A solid bounded by a surface of degree 2. A point p = (x, y, z, 1) is
+/// inside when p^T Q p <= 0, for a 4x4 matrix of coefficients Q. Many of
+/// these shapes are infinite, and can be cut down to size with an
+/// Intersection.
+#[derive(Debug)]
+pub struct Quadric {
+    coefficients: Matrix4<f64>,
+}
+
+impl Quadric {
+    /// the matrix doesn't need to be symmetric, only p^T Q p matters.
+    pub fn new(coefficients: Matrix4<f64>) -> Quadric {
+        Quadric {
+            coefficients: (coefficients + coefficients.transpose()) * 0.5,
+        }
+    }
+
+    /// a sphere stretched to the given radius along each axis.
+    pub fn ellipsoid(x_radius: f64, y_radius: f64, z_radius: f64) -> Quadric {
+        Quadric::new(Matrix4::from_diagonal(&Vector4::new(
+            1.0 / (x_radius * x_radius),
+            1.0 / (y_radius * y_radius),
+            1.0 / (z_radius * z_radius),
+            -1.0,
+        )))
+    }
+
+    /// a hyperboloid of one sheet around the z axis, like a cooling tower.
+    /// x^2 / a^2 + y^2 / b^2 - z^2 / c^2 = 1, where a and b are the x and y
+    /// radii of the narrowest part at z = 0 and c is z_radius. The radii grow
+    /// by a factor of sqrt(1 + z^2 / c^2), so they double at z = sqrt(3) c.
+    pub fn hyperboloid(x_radius: f64, y_radius: f64, z_radius: f64) -> Quadric {
+        Quadric::new(Matrix4::from_diagonal(&Vector4::new(
+            1.0 / (x_radius * x_radius),
+            1.0 / (y_radius * y_radius),
+            -1.0 / (z_radius * z_radius),
+            -1.0,
+        )))
+    }
+
+    /// a bowl opening upwards from the origin, x^2 + y^2 <= 4 f z. Light
+    /// travelling down the z axis is reflected through the focus at
+    /// (0, 0, focal_length).
+    pub fn paraboloid(focal_length: f64) -> Quadric {
+        let mut coefficients = Matrix4::from_diagonal(&Vector4::new(1.0, 1.0, 0.0, 0.0));
+        coefficients[(2, 3)] = -2.0 * focal_length;
+        coefficients[(3, 2)] = -2.0 * focal_length;
+        Quadric::new(coefficients)
+    }
+
+    fn normal(&self, point: &Position) -> Direction {
+        let gradient = self.coefficients * Vector4::new(point.x(), point.y(), point.z(), 1.0);
+        Direction::new(gradient.x, gradient.y, gradient.z).normalise()
+    }
+
+    fn spans(&self, ray: &GeometricRay) -> Vec<Span> {
+        let (origin, direction) = (ray.origin(), ray.direction());
+        let o = Vector4::new(origin.x(), origin.y(), origin.z(), 1.0);
+        let d = Vector4::new(direction.x(), direction.y(), direction.z(), 0.0);
+        let q = &self.coefficients;
+
+        Span::quadratic(
+            d.dot(&(q * d)),
+            2.0 * d.dot(&(q * o)),
+            o.dot(&(q * o)),
+            |t| self.normal(&origin.move_along(direction, t)),
+        )
+    }
+}
+
+impl Shape for Quadric {
+    fn collision(&self, ray: &GeometricRay) -> Option<Collision> {
+        self.spans(ray)
+            .into_iter()
+            .find_map(|span| span.collision(ray))
+    }
+
+    fn collision_in(&self, ray: &GeometricRay) -> Option<Collision> {
+        self.spans(ray)
+            .into_iter()
+            .find_map(|span| span.collision_in(ray))
+    }
+
+    /// only ellipsoids are bounded.
+    fn bounds(&self) -> Option<Bounds> {
+        let q = &self.coefficients;
+        let quadratic = Matrix3::new(
+            q[(0, 0)],
+            q[(0, 1)],
+            q[(0, 2)],
+            q[(1, 0)],
+            q[(1, 1)],
+            q[(1, 2)],
+            q[(2, 0)],
+            q[(2, 1)],
+            q[(2, 2)],
+        );
+        let linear = Vector3::new(q[(0, 3)], q[(1, 3)], q[(2, 3)]);
+        // the quadratic part must be positive definite for the shape to be
+        // an ellipsoid.
+        quadratic.cholesky()?;
+        let inverse = quadratic.try_inverse()?;
+
+        // moving the origin to the centre leaves
+        // (p - centre)^T A (p - centre) + constant <= 0.
+        let centre = -(inverse * linear);
+        let constant = q[(3, 3)] + linear.dot(&centre);
+        if constant >= 0.0 {
+            return None;
+        }
+        let extent = |axis: usize| f64::sqrt(-constant * inverse[(axis, axis)]);
+        let (x, y, z) = (extent(0), extent(1), extent(2));
+        Some(Bounds::new(
+            Position::new(centre.x - x, centre.y - y, centre.z - z),
+            Position::new(centre.x + x, centre.y + y, centre.z + z),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::tests::assert_hit;
+
+    #[test]
+    fn ellipsoid_enters_and_leaves() {
+        let ellipsoid = Quadric::ellipsoid(1.0, 2.0, 3.0);
+        let below = GeometricRay::new(Position::new(0.0, 0.0, -5.0), Direction::UP, 0.0);
+        assert_hit(ellipsoid.collision(&below), 2.0, &Direction::DOWN);
+        let side = GeometricRay::new(Position::new(0.0, -5.0, 0.0), Direction::FORWARDS, 0.0);
+        assert_hit(ellipsoid.collision(&side), 3.0, &Direction::BACKWARDS);
+
+        let inside = GeometricRay::new(Position::ORIGIN, Direction::UP, 0.0);
+        assert!(ellipsoid.collision(&inside).is_none());
+        assert_hit(ellipsoid.collision_in(&inside), 3.0, &Direction::UP);
+    }
+
+    #[test]
+    fn hyperboloid_enters_and_leaves() {
+        let hyperboloid = Quadric::hyperboloid(1.0, 1.0, 1.0);
+        let side = GeometricRay::new(Position::new(5.0, 0.0, 0.0), Direction::LEFT, 0.0);
+        assert_hit(hyperboloid.collision(&side), 4.0, &Direction::RIGHT);
+        // at z = 1 the radius is sqrt(2).
+        let raised = GeometricRay::new(Position::new(0.0, -5.0, 1.0), Direction::FORWARDS, 0.0);
+        assert_hit(
+            hyperboloid.collision(&raised),
+            5.0 - 2f64.sqrt(),
+            &Direction::new(0.0, -2f64.sqrt(), -1.0),
+        );
+
+        let inside = GeometricRay::new(Position::ORIGIN, Direction::RIGHT, 0.0);
+        assert_hit(hyperboloid.collision_in(&inside), 1.0, &Direction::RIGHT);
+        // along the axis the ray never leaves.
+        let axis = GeometricRay::new(Position::ORIGIN, Direction::UP, 0.0);
+        assert!(hyperboloid.collision_in(&axis).is_none());
+    }
+}
@@ -1,4 +1,5 @@
-use crate::shape::{Collision, Direction, Position, Shape};
+use crate::ray::GeometricRay;
+use crate::shape::{Bounds, Collision, Shape, SurfaceSample};
 use crate::vec3::Vec3;
 
 #[derive(Debug)]
@@ -17,19 +18,41 @@ impl<T> Scale<T> {
 }
 
 impl<T: Shape> Shape for Scale<T> {
-    fn collision(&self, origin: &Position, direction: &Direction) -> Option<(Collision)> {
-        let new_origin = origin.reduce_vec(&self.scale);
-        let new_direction = direction.reduce_vec(&self.scale);
+    fn collision(&self, ray: &GeometricRay) -> Option<(Collision)> {
+        let new_ray = ray.reduce_vec(&self.scale);
         self.shape
-            .collision_in(&new_origin, &new_direction)
+            .collision_in(&new_ray)
             .map(|collision| collision.scale(&self.scale))
     }
 
-    fn collision_in(&self, origin: &Position, direction: &Direction) -> Option<(Collision)> {
-        let new_origin = origin.reduce_vec(&self.scale);
-        let new_direction = direction.reduce_vec(&self.scale);
+    fn collision_in(&self, ray: &GeometricRay) -> Option<(Collision)> {
+        let new_ray = ray.reduce_vec(&self.scale);
         self.shape
-            .collision_in(&new_origin, &new_direction)
+            .collision_in(&new_ray)
             .map(|collision| collision.scale(&self.scale))
     }
+
+    fn sample_surface(&self) -> Option<SurfaceSample> {
+        self.shape.sample_surface().map(|sample| {
+            // the area of a surface element changes depending on its
+            // orientation relative to the scaling.
+            let normal = sample.normal.reduce_vec(&self.scale);
+            let volume = self.scale.x * self.scale.y * self.scale.z;
+            SurfaceSample {
+                position: sample.position.scale_vec(&self.scale),
+                normal: normal.normalise(),
+                pdf: sample.pdf / (volume * normal.len()).abs(),
+            }
+        })
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        let bounds = self.shape.bounds()?;
+        Bounds::from_points(
+            bounds
+                .corners()
+                .iter()
+                .map(|corner| corner.scale_vec(&self.scale)),
+        )
+    }
 }
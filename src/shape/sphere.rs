@@ -1,4 +1,6 @@
-use crate::shape::{Collision, Direction, Position, Shape};
+use crate::ray::GeometricRay;
+use crate::shape::{Bounds, Collision, Direction, Position, Shape, SurfaceSample};
+use std::f64::consts::PI;
 
 #[derive(Debug)]
 pub struct Sphere {
@@ -12,7 +14,8 @@ impl Sphere {
 }
 
 impl Shape for Sphere {
-    fn collision(&self, origin: &Position, direction: &Direction) -> Option<(Collision)> {
+    fn collision(&self, ray: &GeometricRay) -> Option<(Collision)> {
+        let (origin, direction) = (ray.origin(), ray.direction());
         let b: f64 = 2.0 * (direction.dot_position(origin));
         let a: f64 = direction.dot(&direction);
         let c: f64 = origin.dot(&origin) - self.radius * self.radius;
@@ -36,7 +39,8 @@ impl Shape for Sphere {
         ))
     }
 
-    fn collision_in(&self, origin: &Position, direction: &Direction) -> Option<(Collision)> {
+    fn collision_in(&self, ray: &GeometricRay) -> Option<(Collision)> {
+        let (origin, direction) = (ray.origin(), ray.direction());
         let b: f64 = 2.0 * (direction.dot_position(origin));
         let a: f64 = direction.dot(&direction);
         let c: f64 = origin.dot(&origin) - self.radius * self.radius;
@@ -59,4 +63,21 @@ impl Shape for Sphere {
             collision,
         ))
     }
+
+    fn sample_surface(&self) -> Option<SurfaceSample> {
+        let normal = Direction::random();
+        let area = 4.0 * PI * self.radius * self.radius;
+        Some(SurfaceSample {
+            position: normal.to_position().scale(self.radius),
+            normal,
+            pdf: 1.0 / area,
+        })
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Some(Bounds::new(
+            Position::new(-self.radius, -self.radius, -self.radius),
+            Position::new(self.radius, self.radius, self.radius),
+        ))
+    }
 }
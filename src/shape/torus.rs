@@ -0,0 +1,117 @@
+use crate::ray::GeometricRay;
+use crate::shape::convex::{Intersect, Span};
+use crate::shape::polynomial;
+use crate::shape::{Bounds, Collision, Direction, Position, Shape};
+
+/// A solid ring centred on the origin, lying flat around the z axis.
+#[derive(Debug)]
+pub struct Torus {
+    /// the distance from the centre of the ring to the middle of the tube.
+    major_radius: f64,
+    /// the radius of the tube.
+    minor_radius: f64,
+}
+
+impl Torus {
+    pub fn new(major_radius: f64, minor_radius: f64) -> Torus {
+        Torus {
+            major_radius,
+            minor_radius,
+        }
+    }
+
+    fn normal(&self, point: &Position) -> Direction {
+        // the gradient of the quartic.
+        let radius2 = self.major_radius * self.major_radius;
+        let k = point.len_sq() + radius2 - self.minor_radius * self.minor_radius;
+        Direction::new(
+            point.x() * (k - 2.0 * radius2),
+            point.y() * (k - 2.0 * radius2),
+            point.z() * k,
+        )
+        .normalise()
+    }
+
+    /// the parts of a ray inside the torus. A ray can pass through the tube
+    /// twice, on either side of the hole.
+    fn spans(&self, ray: &GeometricRay) -> Vec<Span> {
+        let (origin, direction) = (ray.origin(), ray.direction());
+        // points inside satisfy
+        // (x^2 + y^2 + z^2 + R^2 - r^2)^2 - 4 R^2 (x^2 + y^2) < 0.
+        let radius2 = self.major_radius * self.major_radius;
+        let dd = direction.dot(direction);
+        let od = direction.dot_position(origin);
+        let k = origin.len_sq() + radius2 - self.minor_radius * self.minor_radius;
+        let flat_dd = direction.x() * direction.x() + direction.y() * direction.y();
+        let flat_od = origin.x() * direction.x() + origin.y() * direction.y();
+        let flat_oo = origin.x() * origin.x() + origin.y() * origin.y();
+
+        let roots = polynomial::roots(&[
+            dd * dd,
+            4.0 * dd * od,
+            4.0 * od * od + 2.0 * dd * k - 4.0 * radius2 * flat_dd,
+            4.0 * od * k - 8.0 * radius2 * flat_od,
+            k * k - 4.0 * radius2 * flat_oo,
+        ]);
+
+        // the quartic is positive far from the torus, so the ray is inside
+        // between pairs of roots.
+        let at = |t: f64| Intersect {
+            t,
+            normal: self.normal(&origin.move_along(direction, t)),
+        };
+        roots
+            .chunks(2)
+            .filter(|pair| pair.len() == 2)
+            .map(|pair| Span {
+                enter: at(pair[0]),
+                exit: at(pair[1]),
+            })
+            .collect()
+    }
+}
+
+impl Shape for Torus {
+    fn collision(&self, ray: &GeometricRay) -> Option<Collision> {
+        self.spans(ray)
+            .into_iter()
+            .find_map(|span| span.collision(ray))
+    }
+
+    fn collision_in(&self, ray: &GeometricRay) -> Option<Collision> {
+        self.spans(ray)
+            .into_iter()
+            .find_map(|span| span.collision_in(ray))
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        let radius = self.major_radius + self.minor_radius;
+        Some(Bounds::new(
+            Position::new(-radius, -radius, -self.minor_radius),
+            Position::new(radius, radius, self.minor_radius),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::tests::assert_hit;
+
+    #[test]
+    fn enters_and_leaves() {
+        let torus = Torus::new(2.0, 0.5);
+        let outside = GeometricRay::new(Position::new(0.0, -5.0, 0.0), Direction::FORWARDS, 0.0);
+        assert_hit(torus.collision(&outside), 2.5, &Direction::BACKWARDS);
+        // from the hole the ray meets the far side of the ring.
+        let hole = GeometricRay::new(Position::ORIGIN, Direction::FORWARDS, 0.0);
+        assert_hit(torus.collision(&hole), 1.5, &Direction::BACKWARDS);
+        let above = GeometricRay::new(Position::new(2.0, 0.0, 5.0), Direction::DOWN, 0.0);
+        assert_hit(torus.collision(&above), 4.5, &Direction::UP);
+
+        let inside = GeometricRay::new(Position::new(0.0, -2.0, 0.0), Direction::FORWARDS, 0.0);
+        assert_hit(torus.collision_in(&inside), 0.5, &Direction::FORWARDS);
+        let inside = GeometricRay::new(Position::new(2.0, 0.0, 0.0), Direction::DOWN, 0.0);
+        assert_hit(torus.collision_in(&inside), 0.5, &Direction::DOWN);
+    }
+}
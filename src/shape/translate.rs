@@ -1,4 +1,5 @@
-use crate::shape::{Collision, Direction, Position, Shape};
+use crate::ray::GeometricRay;
+use crate::shape::{Bounds, Collision, Position, Shape, SurfaceSample};
 
 #[derive(Debug)]
 pub struct Translate<T> {
@@ -16,17 +17,33 @@ impl<T> Translate<T> {
 }
 
 impl<T: Shape> Shape for Translate<T> {
-    fn collision(&self, origin: &Position, direction: &Direction) -> Option<(Collision)> {
-        let new_origin = origin - &self.translation;
+    fn collision(&self, ray: &GeometricRay) -> Option<(Collision)> {
+        let new_ray = ray.with_origin(ray.origin() - &self.translation);
         self.shape
-            .collision(&new_origin, direction)
+            .collision(&new_ray)
             .map(|collision| collision.translate(&self.translation))
     }
 
-    fn collision_in(&self, origin: &Position, direction: &Direction) -> Option<(Collision)> {
-        let new_origin = origin - &self.translation;
+    fn collision_in(&self, ray: &GeometricRay) -> Option<(Collision)> {
+        let new_ray = ray.with_origin(ray.origin() - &self.translation);
         self.shape
-            .collision_in(&new_origin, direction)
+            .collision_in(&new_ray)
             .map(|collision| collision.translate(&self.translation))
     }
+
+    fn sample_surface(&self) -> Option<SurfaceSample> {
+        self.shape.sample_surface().map(|sample| SurfaceSample {
+            position: &sample.position + &self.translation,
+            ..sample
+        })
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        self.shape.bounds().map(|bounds| {
+            Bounds::new(
+                &bounds.min + &self.translation,
+                &bounds.max + &self.translation,
+            )
+        })
+    }
 }
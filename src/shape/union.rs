@@ -1,4 +1,5 @@
-use crate::shape::{Collision, Direction, Position, Shape};
+use crate::ray::GeometricRay;
+use crate::shape::{Bounds, Collision, Shape};
 
 use std::cmp::Ordering::Equal;
 
@@ -13,25 +14,28 @@ impl<'a> Union<'a> {
 }
 
 impl<'a> Shape for Union<'a> {
-    fn collision(&self, origin: &Position, direction: &Direction) -> Option<(Collision)> {
-        let collisions = self
-            .shapes
-            .iter()
-            .filter_map(|shape| shape.collision(origin, direction));
+    fn collision(&self, ray: &GeometricRay) -> Option<(Collision)> {
+        let collisions = self.shapes.iter().filter_map(|shape| shape.collision(ray));
 
         collisions.min_by(|collision1, collision2| {
             collision1.t().partial_cmp(&collision2.t()).unwrap_or(Equal)
         })
     }
 
-    fn collision_in(&self, origin: &Position, direction: &Direction) -> Option<(Collision)> {
+    fn collision_in(&self, ray: &GeometricRay) -> Option<(Collision)> {
         let collisions = self
             .shapes
             .iter()
-            .filter_map(|shape| shape.collision_in(origin, direction));
+            .filter_map(|shape| shape.collision_in(ray));
 
         collisions.min_by(|collision1, collision2| {
             collision1.t().partial_cmp(&collision2.t()).unwrap_or(Equal)
         })
     }
+
+    fn bounds(&self) -> Option<Bounds> {
+        let mut bounds = self.shapes.iter().map(|shape| shape.bounds());
+        let first = bounds.next()??;
+        bounds.try_fold(first, |total, bounds| Some(total.union(&bounds?)))
+    }
 }